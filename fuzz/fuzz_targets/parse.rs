@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rb_date_parser::date_parser::date_parse;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(string) = std::str::from_utf8(data) {
+        date_parse(string, true);
+        let _ = rb_date_parser::parse(string);
+        let _ = rb_date_parser::parse_candidates(string);
+        let _ = rb_date_parser::find_dates(string).count();
+        let _ = rb_date_parser::date_parser::time::iso8601(string);
+        let _ = rb_date_parser::date_parser::time::rfc3339(string);
+        let _ = rb_date_parser::date_parser::time::xmlschema(string);
+        let _ = rb_date_parser::date_parser::time::rfc2822(string);
+        let _ = rb_date_parser::date_parser::time::httpdate(string);
+        let _ = rb_date_parser::date_parser::time::jisx0301(string);
+        let _ = rb_date_parser::date_parser::time::rfc9557(string);
+    }
+});
@@ -0,0 +1,118 @@
+use serde::Serialize;
+
+use crate::DateTime;
+use super::parse::{check_classes, date_chars, date_parse_head, date_parse_tail, DateOrder, YearPivot, DATE_PARSERS};
+
+/// One reading of an input by `parse_candidates`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Candidate {
+    pub datetime: DateTime,
+    /// The sub-parser that read the date, such as `parse_sla`, or the one
+    /// that read the time (`parse_time`) when there is no date.
+    pub parser: &'static str,
+    /// The date order the sub-parser was given, for `parse_sla` and
+    /// `parse_dot`. `None` is Ruby's reading.
+    pub date_order: Option<DateOrder>,
+    /// From 0 to 1, the share of the input the reading accounts for, times
+    /// 0.9 for readings other than the one `date_parse` picks.
+    pub confidence: f64,
+}
+
+// The fields of the reading are in range, so that the candidates don't
+// include a thirteenth month or a February 30th.
+pub(super) fn plausible(datetime: &DateTime) -> bool {
+    if datetime.mon.is_some_and(|mon| !(1..=12).contains(&mon)) || datetime.mday.is_some_and(|mday| !(1..=31).contains(&mday)) {
+        return false;
+    }
+    if let (Some(year), Some(mon), Some(mday)) = (datetime.year, datetime.mon, datetime.mday) {
+        if chrono::NaiveDate::from_ymd_opt(year, mon, mday).is_none() {
+            return false;
+        }
+    }
+    datetime.hour.is_none_or(|hour| hour <= 24) && datetime.min.is_none_or(|min| min <= 59) && datetime.sec.is_none_or(|sec| sec <= 60)
+}
+
+// Every field +a+ has, +b+ has with the same value, so +a+ is a partial
+// reading of what +b+ reads.
+fn subsumed(a: &DateTime, b: &DateTime) -> bool {
+    fn agree<T: PartialEq>(a: &Option<T>, b: &Option<T>) -> bool {
+        a.is_none() || a == b
+    }
+    agree(&a.year, &b.year) && agree(&a.mon, &b.mon) && agree(&a.mday, &b.mday) && agree(&a.yday, &b.yday)
+        && agree(&a.wday, &b.wday) && agree(&a.cwyear, &b.cwyear) && agree(&a.cweek, &b.cweek) && agree(&a.cwday, &b.cwday)
+        && agree(&a.hour, &b.hour) && agree(&a.min, &b.min) && agree(&a.sec, &b.sec) && agree(&a.sec_fraction, &b.sec_fraction)
+        && agree(&a.zone, &b.zone) && a.bc == b.bc
+}
+
+fn non_space_len(string: &str) -> usize {
+    string.chars().filter(|c| !c.is_whitespace()).count()
+}
+
+/// Returns every plausible reading of the input instead of the first one.
+/// Each date sub-parser of `date_parse` that matches is tried on its own,
+/// `parse_sla` and `parse_dot` once for each date order, so "03/04/2024"
+/// is read both as 3 April and as 4 March. The readings with fields in
+/// range are returned, the most confident first, leaving out the ones that
+/// are just part of a more confident reading. Years are completed as
+/// `date_parse` does with `comp`.
+///
+/// parse_candidates('03/04/2024')
+///     #=> [{:datetime=>{:year=>2024, :mon=>4, :mday=>3}, :parser=>"parse_sla", :confidence=>1.0},
+///          {:datetime=>{:year=>2024, :mon=>3, :mday=>4}, :parser=>"parse_sla", :date_order=>Mdy, :confidence=>0.9}]
+pub fn parse_candidates(input: &str) -> Vec<Candidate> {
+    let pivot = Some(YearPivot::default());
+    let (head, rest) = date_parse_head(input, true, &mut None);
+    let total = non_space_len(&date_chars(input));
+
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut first = true;
+    let mut push = |mut datetime: DateTime, mut string: String, parser: &'static str, date_order: Option<DateOrder>, first: bool| {
+        date_parse_tail(&mut string, &mut datetime, pivot, &mut None);
+        if !plausible(&datetime) || candidates.iter().any(|x| x.datetime == datetime) {
+            return;
+        }
+        let coverage = if total == 0 { 0.0 } else { 1.0 - non_space_len(&string) as f64 / total as f64 };
+        let confidence = if first { coverage } else { coverage * 0.9 };
+        candidates.push(Candidate { datetime, parser, date_order, confidence });
+    };
+
+    for (parser, classes, parse) in &DATE_PARSERS {
+        if !check_classes(&rest, *classes) {
+            continue;
+        }
+        let orders: &[Option<DateOrder>] = match *parser {
+            "parse_sla" | "parse_dot" => &[None, Some(DateOrder::Mdy), Some(DateOrder::Dmy), Some(DateOrder::Ymd)],
+            _ => &[None],
+        };
+        let mut matched = false;
+        for order in orders {
+            let mut datetime = head.clone();
+            let mut string = rest.clone();
+            if parse(&mut string, &mut datetime, *order) {
+                matched = true;
+                // date_parse takes the first sub-parser that matches, with
+                // Ruby's date order.
+                push(datetime, string, parser, *order, first && order.is_none());
+            }
+        }
+        first &= !matched;
+    }
+
+    if first {
+        // No date, just the time or the day of the week.
+        let parser = if head.hour.is_some() { "parse_time" } else if head.wday.is_some() { "parse_day" } else { "parse_iana_zone" };
+        if head.hour.is_some() || head.wday.is_some() || head.zone.is_some() {
+            push(head, rest, parser, None, true);
+        }
+    }
+
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    // Readings that only cover part of a more confident one add nothing.
+    let mut distinct: Vec<Candidate> = Vec::new();
+    for candidate in candidates {
+        if !distinct.iter().any(|x| subsumed(&candidate.datetime, &x.datetime)) {
+            distinct.push(candidate);
+        }
+    }
+    distinct
+}
@@ -0,0 +1,348 @@
+use std::fmt;
+use std::ops::{Add, Shl, Shr, Sub};
+
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::{DateTime, ErrorKind, ParseError};
+use super::parse::{date_parse_internal, YearPivot};
+use super::relative::parse_relative;
+use super::time::{field_i32, has_date, located, unrepresentable_year, validated_ymd, week_based_date};
+
+// The ways of giving a date that Date.parse completes, in the order of its
+// table, which decides ties.
+#[derive(Clone, Copy)]
+enum Form {
+    Ordinal,
+    Civil,
+    Commercial,
+    Wday,
+    Wnum0,
+    Wnum1,
+    // Mixes of the week based fields, which are left as they are.
+    Other,
+}
+
+/// Parses the date in +input+ the way Ruby's `Date.parse` does. Missing
+/// fields are completed from today (in UTC) and the time of day and zone,
+/// if any, are disregarded, so the date is the one written in the input.
+/// Relative dates such as "tomorrow" and "next Friday" are taken from
+/// today.
+///
+/// parse_date('2001-02-03T04:05:06+07:00') #=> 2001-02-03
+/// parse_date('Feb 3')                     #=> 2001-02-03 (this year)
+/// parse_date('10:00')                     #=> Err(NoDateFound)
+pub fn parse_date(input: &str) -> crate::Result<NaiveDate> {
+    parse_date_with_today(input, chrono::Utc::now().date_naive())
+}
+
+/// Same as `parse_date`, with missing fields taken from +today+.
+///
+/// parse_date_with_today('Fri', 2001-11-29)  #=> 2001-11-30
+/// parse_date_with_today('W10', 2001-11-29)  #=> 2001-03-05
+/// parse_date_with_today('the 3rd', 2001-11-29) #=> 2001-11-03
+/// parse_date_with_today('next Friday', 2001-11-29) #=> 2001-11-30
+pub fn parse_date_with_today(input: &str, today: NaiveDate) -> crate::Result<NaiveDate> {
+    let pivot = Some(YearPivot::default());
+    let now = today.and_time(chrono::NaiveTime::MIN).and_utc().fixed_offset();
+    if let Some((relative, rest)) = parse_relative(input, &now) {
+        if !has_date(&date_parse_internal(&rest, pivot, None).0) {
+            return relative.map(|x| x.date()).map_err(|err| err.with_input(input));
+        }
+    }
+    let (d, _) = date_parse_internal(input, pivot, None);
+    complete(&d, today)
+        .and_then(|d| resolve(&d, today))
+        .map_err(|err| located(err, input, pivot, None))
+}
+
+// Fills in the fields of +d+ that Date.parse takes from +today+, going by
+// the way of giving the date that most of them fit. The time fields count
+// for every way alike, so a time alone isn't a date.
+fn complete(d: &DateTime, today: NaiveDate) -> crate::Result<DateTime> {
+    let count = |fields: &[bool]| fields.iter().filter(|x| **x).count();
+    let forms = [
+        (Form::Ordinal, count(&[d.year.is_some(), d.yday.is_some()])),
+        (Form::Civil, count(&[d.year.is_some(), d.mon.is_some(), d.mday.is_some()])),
+        (Form::Commercial, count(&[d.cwyear.is_some(), d.cweek.is_some(), d.cwday.is_some()])),
+        (Form::Wday, count(&[d.wday.is_some()])),
+        (Form::Wnum0, count(&[d.year.is_some(), d.wnum0.is_some(), d.wday.is_some()])),
+        (Form::Wnum1, count(&[d.year.is_some(), d.wnum1.is_some(), d.wday.is_some()])),
+        (Form::Other, count(&[d.cwyear.is_some(), d.cweek.is_some(), d.wday.is_some()])),
+        (Form::Other, count(&[d.year.is_some(), d.wnum0.is_some(), d.cwday.is_some()])),
+        (Form::Other, count(&[d.year.is_some(), d.wnum1.is_some(), d.cwday.is_some()])),
+    ];
+    let (form, fields) = forms.iter().fold((Form::Other, 0), |best, x| if x.1 > best.1 { *x } else { best });
+    if fields == 0 {
+        return Err(ParseError::new(ErrorKind::NoDateFound));
+    }
+
+    // The fields before the first one given come from today, and the
+    // fields after it start the period.
+    let mut d = d.clone();
+    match form {
+        Form::Ordinal => {
+            d.year.get_or_insert(today.year());
+            d.yday.get_or_insert(1);
+        }
+        Form::Civil => {
+            if d.year.is_none() {
+                d.year = Some(today.year());
+                if d.mon.is_none() {
+                    d.mon = Some(today.month());
+                    d.mday.get_or_insert(today.day());
+                }
+            }
+            d.mon.get_or_insert(1);
+            d.mday.get_or_insert(1);
+        }
+        Form::Commercial => {
+            if d.cwyear.is_none() {
+                d.cwyear = Some(today.iso_week().year());
+                if d.cweek.is_none() {
+                    d.cweek = Some(today.iso_week().week());
+                    d.cwday.get_or_insert(today.weekday().number_from_monday());
+                }
+            }
+            d.cwday.get_or_insert(1);
+        }
+        Form::Wday => {
+            // The day of the week in the week of today, Sunday to Saturday.
+            let days = d.wday.unwrap_or_default() as i64 - today.weekday().num_days_from_sunday() as i64;
+            let date = today.checked_add_signed(chrono::Duration::days(days))
+                .ok_or_else(|| unrepresentable_year(today.year()))?;
+            (d.year, d.mon, d.mday) = (Some(date.year()), Some(date.month()), Some(date.day()));
+        }
+        Form::Wnum0 | Form::Wnum1 => {
+            let wnum0 = matches!(form, Form::Wnum0);
+            let wnum = if wnum0 { &mut d.wnum0 } else { &mut d.wnum1 };
+            if d.year.is_none() {
+                d.year = Some(today.year());
+                if wnum.is_none() {
+                    let format = if wnum0 { "%U" } else { "%W" };
+                    *wnum = today.format(format).to_string().parse().ok();
+                    d.wday.get_or_insert(today.weekday().num_days_from_sunday() as i32);
+                }
+            }
+            d.wday.get_or_insert(if wnum0 { 0 } else { 1 });
+        }
+        Form::Other => {}
+    }
+    Ok(d)
+}
+
+// The date of the completed fields, trying each way of giving it in the
+// order Date.parse does.
+fn resolve(d: &DateTime, today: NaiveDate) -> crate::Result<NaiveDate> {
+    if let (Some(year), Some(yday)) = (d.year, d.yday) {
+        let jan1 = NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(|| unrepresentable_year(year))?;
+        let days = if jan1.leap_year() { 366 } else { 365 };
+        return u32::try_from(yday).ok()
+            .and_then(|yday| NaiveDate::from_yo_opt(year, yday))
+            .ok_or_else(|| ParseError::out_of_range(ErrorKind::InvalidYday, "yday", yday, Some(1..=days)));
+    }
+    if let (Some(year), Some(mon), Some(mday)) = (d.year, d.mon, d.mday) {
+        let (year, mon, mday) = validated_ymd(year, field_i32("mon", mon)?, field_i32("mday", mday)?)?;
+        return NaiveDate::from_ymd_opt(year, mon, mday).ok_or_else(|| unrepresentable_year(year));
+    }
+
+    // week_based_date takes the fields given and nothing from today.
+    let now = today.and_time(chrono::NaiveTime::MIN).and_utc().fixed_offset();
+    let week_based = |x: DateTime| week_based_date(&x, &now)?.ok_or_else(|| ParseError::new(ErrorKind::NoDateFound));
+    let cwday = d.cwday.or_else(|| d.wday.map(|w| if w == 0 { 7 } else { w as u32 }));
+    if let (Some(cwyear), Some(cweek), Some(cwday)) = (d.cwyear, d.cweek, cwday) {
+        return week_based(DateTime { cwyear: Some(cwyear), cweek: Some(cweek), cwday: Some(cwday), ..Default::default() });
+    }
+    let wday = d.wday.or_else(|| d.cwday.map(|x| (x % 7) as i32));
+    if let (Some(year), Some(wday)) = (d.year, wday) {
+        if d.wnum0.is_some() || d.wnum1.is_some() {
+            let (wnum0, wnum1) = if d.wnum0.is_some() { (d.wnum0, None) } else { (None, d.wnum1) };
+            return week_based(DateTime { year: Some(year), wnum0, wnum1, wday: Some(wday), ..Default::default() });
+        }
+    }
+    Err(ParseError::new(ErrorKind::NoDateFound))
+}
+
+/// A calendar date with the accessors and arithmetic of Ruby's `Date`. It
+/// has no time of day nor zone, so it doesn't move when serialized.
+///
+/// ```
+/// use rb_date_parser::Date;
+///
+/// let date = Date::parse("Sat Aug 28 2021 23:30 -0500").unwrap();
+/// assert_eq!(date.to_string(), "2021-08-28");
+/// assert_eq!((date >> 6).to_string(), "2022-02-28");
+/// assert_eq!(date.cweek(), 34);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(NaiveDate);
+
+impl Date {
+    /// The date of +year+, +mon+ and +mday+, if it exists.
+    pub fn new(year: i32, mon: u32, mday: u32) -> Option<Date> {
+        NaiveDate::from_ymd_opt(year, mon, mday).map(Date)
+    }
+
+    /// See `parse_date`.
+    pub fn parse(input: &str) -> crate::Result<Date> {
+        parse_date(input).map(Date)
+    }
+
+    /// See `parse_date_with_today`.
+    pub fn parse_with_today(input: &str, today: Date) -> crate::Result<Date> {
+        parse_date_with_today(input, today.0).map(Date)
+    }
+
+    pub fn year(&self) -> i32 {
+        self.0.year()
+    }
+
+    pub fn mon(&self) -> u32 {
+        self.0.month()
+    }
+
+    pub fn mday(&self) -> u32 {
+        self.0.day()
+    }
+
+    /// The day of the week, Sunday being 0.
+    pub fn wday(&self) -> u32 {
+        self.0.weekday().num_days_from_sunday()
+    }
+
+    pub fn yday(&self) -> u32 {
+        self.0.ordinal()
+    }
+
+    /// The ISO 8601 week based year.
+    pub fn cwyear(&self) -> i32 {
+        self.0.iso_week().year()
+    }
+
+    pub fn cweek(&self) -> u32 {
+        self.0.iso_week().week()
+    }
+
+    /// The day of the week, Monday being 1 and Sunday 7.
+    pub fn cwday(&self) -> u32 {
+        self.0.weekday().number_from_monday()
+    }
+
+    /// The Julian day number.
+    pub fn jd(&self) -> i64 {
+        self.0.num_days_from_ce() as i64 + 1_721_425
+    }
+
+    pub fn leap(&self) -> bool {
+        self.0.leap_year()
+    }
+
+    pub fn next_day(&self, n: i64) -> Date {
+        *self + n
+    }
+
+    pub fn prev_day(&self, n: i64) -> Date {
+        *self - n
+    }
+
+    pub fn next_month(&self, n: i32) -> Date {
+        *self >> n
+    }
+
+    pub fn prev_month(&self, n: i32) -> Date {
+        *self << n
+    }
+
+    pub fn next_year(&self, n: i32) -> Date {
+        *self >> (12 * n)
+    }
+
+    pub fn prev_year(&self, n: i32) -> Date {
+        *self << (12 * n)
+    }
+
+    /// Formats the date with the directives of `strftime`, the time of day
+    /// being midnight in UTC.
+    pub fn strftime(&self, format: &str) -> String {
+        let time = self.0.and_time(chrono::NaiveTime::MIN).and_utc().fixed_offset();
+        super::strftime(&time, format)
+    }
+
+    pub fn to_naive_date(&self) -> NaiveDate {
+        self.0
+    }
+
+    fn add_months(self, months: i64) -> Date {
+        let shifted = match u32::try_from(months.unsigned_abs()) {
+            Ok(n) if months < 0 => self.0.checked_sub_months(Months::new(n)),
+            Ok(n) => self.0.checked_add_months(Months::new(n)),
+            Err(_) => None,
+        };
+        Date(shifted.expect("date out of range"))
+    }
+}
+
+impl From<NaiveDate> for Date {
+    fn from(date: NaiveDate) -> Date {
+        Date(date)
+    }
+}
+
+impl From<Date> for NaiveDate {
+    fn from(date: Date) -> NaiveDate {
+        date.0
+    }
+}
+
+/// The date in ISO 8601 format, like Ruby's `Date#to_s`.
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format("%Y-%m-%d"))
+    }
+}
+
+/// The date +days+ days later. Panics when out of the range of `NaiveDate`,
+/// as its operators do.
+impl Add<i64> for Date {
+    type Output = Date;
+
+    fn add(self, days: i64) -> Date {
+        Date(self.0 + chrono::Duration::days(days))
+    }
+}
+
+/// The date +days+ days earlier.
+impl Sub<i64> for Date {
+    type Output = Date;
+
+    fn sub(self, days: i64) -> Date {
+        Date(self.0 - chrono::Duration::days(days))
+    }
+}
+
+/// The number of days from +other+ to the date.
+impl Sub<Date> for Date {
+    type Output = i64;
+
+    fn sub(self, other: Date) -> i64 {
+        (self.0 - other.0).num_days()
+    }
+}
+
+/// The date +months+ months later, the day being the last of the month when
+/// the month is shorter, as `Date#>>` does: 2001-01-31 >> 1 is 2001-02-28.
+impl Shr<i32> for Date {
+    type Output = Date;
+
+    fn shr(self, months: i32) -> Date {
+        self.add_months(months as i64)
+    }
+}
+
+/// The date +months+ months earlier, as `Date#<<` does.
+impl Shl<i32> for Date {
+    type Output = Date;
+
+    fn shl(self, months: i32) -> Date {
+        self.add_months(-(months as i64))
+    }
+}
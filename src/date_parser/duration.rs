@@ -0,0 +1,374 @@
+use std::fmt;
+
+use chrono::{DateTime, Days, Months, TimeZone};
+use regex::{Captures, Regex, RegexBuilder};
+use serde::Serialize;
+
+use crate::{ErrorKind, ParseError};
+use super::parse::fraction;
+
+/// A duration in calendar units, as read by `parse_duration`. Years, months
+/// and days move the date on the calendar, so a month from January 31st is
+/// February 28th and a day keeps the time of day across a DST change, while
+/// hours, minutes and seconds are elapsed time. Weeks are seven days.
+#[derive(Debug, Default, Clone, Copy, Serialize, PartialEq)]
+pub struct CalendarDuration {
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+    pub hours: i64,
+    pub minutes: i64,
+    pub seconds: i64,
+    pub sec_fraction: f64,
+}
+
+impl CalendarDuration {
+    /// The time +self+ after +t+, or `None` when it is out of range.
+    pub fn since<Tz: TimeZone>(&self, t: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let months = self.years.checked_mul(12)?.checked_add(self.months)?;
+        let t = shift(t, months, |t, n| t.checked_add_months(Months::new(n)), |t, n| t.checked_sub_months(Months::new(n)))?;
+        let t = shift(t, self.days, |t, n| t.checked_add_days(Days::new(n.into())), |t, n| t.checked_sub_days(Days::new(n.into())))?;
+        t.checked_add_signed(self.elapsed()?)
+    }
+
+    /// The time +self+ before +t+, or `None` when it is out of range.
+    pub fn ago<Tz: TimeZone>(&self, t: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        self.negated()?.since(t)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == CalendarDuration::default()
+    }
+
+    // The hours, minutes and seconds.
+    fn elapsed(&self) -> Option<chrono::Duration> {
+        let nanos = (self.sec_fraction * 1_000_000_000.0).round() as i64;
+        chrono::Duration::try_hours(self.hours)?
+            .checked_add(&chrono::Duration::try_minutes(self.minutes)?)?
+            .checked_add(&chrono::Duration::try_seconds(self.seconds)?)?
+            .checked_add(&chrono::Duration::nanoseconds(nanos))
+    }
+
+    // +self+ +n+ times over.
+    pub(super) fn scaled(&self, n: i64) -> Option<CalendarDuration> {
+        Some(CalendarDuration {
+            years: self.years.checked_mul(n)?,
+            months: self.months.checked_mul(n)?,
+            days: self.days.checked_mul(n)?,
+            hours: self.hours.checked_mul(n)?,
+            minutes: self.minutes.checked_mul(n)?,
+            seconds: self.seconds.checked_mul(n)?,
+            sec_fraction: self.sec_fraction * n as f64,
+        })
+    }
+
+    pub(super) fn negated(&self) -> Option<CalendarDuration> {
+        Some(CalendarDuration {
+            years: self.years.checked_neg()?,
+            months: self.months.checked_neg()?,
+            days: self.days.checked_neg()?,
+            hours: self.hours.checked_neg()?,
+            minutes: self.minutes.checked_neg()?,
+            seconds: self.seconds.checked_neg()?,
+            sec_fraction: -self.sec_fraction,
+        })
+    }
+}
+
+// Moves +t+ by +n+ units forward or backward, as chrono takes them unsigned.
+fn shift<T>(t: T, n: i64, forward: impl Fn(T, u32) -> Option<T>, backward: impl Fn(T, u32) -> Option<T>) -> Option<T> {
+    let abs = u32::try_from(n.unsigned_abs()).ok()?;
+    if n < 0 { backward(t, abs) } else { forward(t, abs) }
+}
+
+/// The duration in ISO 8601 format, such as `P1Y2M10DT2H30M`, with a
+/// leading `-` when it is negative.
+impl fmt::Display for CalendarDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let negative = self.negated().filter(|x| x.years >= 0 && x.months >= 0 && x.days >= 0 && x.hours >= 0 &&
+            x.minutes >= 0 && x.seconds >= 0 && x.sec_fraction >= 0.0 && !self.is_zero());
+        let d = negative.unwrap_or(*self);
+        if negative.is_some() {
+            write!(f, "-")?;
+        }
+        write!(f, "P")?;
+        for (value, unit) in [(d.years, "Y"), (d.months, "M"), (d.days, "D")] {
+            if value != 0 {
+                write!(f, "{}{}", value, unit)?;
+            }
+        }
+        let has_seconds = d.seconds != 0 || d.sec_fraction != 0.0;
+        if d.hours != 0 || d.minutes != 0 || has_seconds || (d.years == 0 && d.months == 0 && d.days == 0) {
+            write!(f, "T")?;
+        }
+        for (value, unit) in [(d.hours, "H"), (d.minutes, "M")] {
+            if value != 0 {
+                write!(f, "{}{}", value, unit)?;
+            }
+        }
+        if d.sec_fraction != 0.0 {
+            let seconds = format!("{:.9}", d.seconds as f64 + d.sec_fraction);
+            write!(f, "{}S", seconds.trim_end_matches('0'))
+        } else if has_seconds || d.is_zero() {
+            write!(f, "{}S", d.seconds)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// The units of durations. Fractions of weeks and smaller units are spread
+// over the hours, minutes and seconds; years and months can't be split.
+#[derive(Clone, Copy, PartialEq)]
+enum Unit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl Unit {
+    fn seconds(self) -> Option<i64> {
+        match self {
+            Unit::Year | Unit::Month => None,
+            Unit::Week => Some(7 * 86_400),
+            Unit::Day => Some(86_400),
+            Unit::Hour => Some(3_600),
+            Unit::Minute => Some(60),
+            Unit::Second => Some(1),
+        }
+    }
+
+    fn field(self) -> &'static str {
+        match self {
+            Unit::Year => "years",
+            Unit::Month => "months",
+            Unit::Week | Unit::Day => "days",
+            Unit::Hour => "hours",
+            Unit::Minute => "minutes",
+            Unit::Second => "seconds",
+        }
+    }
+}
+
+impl CalendarDuration {
+    // Adds +number+ of +unit+, which may have a sign and a fraction.
+    fn add_number(&mut self, unit: Unit, number: &str) -> Option<()> {
+        let (whole, digits) = match number.split_once(['.', ',']) {
+            Some((whole, digits)) => (whole, Some(digits)),
+            None => (number, None),
+        };
+        let negative = whole.starts_with('-');
+        if digits.is_some() && unit.seconds().is_none() {
+            return None;
+        }
+        self.add(unit, whole.parse().ok()?, digits.map(|x| (x, negative)))
+    }
+
+    // Adds +whole+ and the fraction in +digits+ of +unit+, negative when it
+    // is marked so.
+    fn add(&mut self, unit: Unit, whole: i64, digits: Option<(&str, bool)>) -> Option<()> {
+        let (field, factor) = match unit {
+            Unit::Year => (&mut self.years, 1),
+            Unit::Month => (&mut self.months, 1),
+            Unit::Week => (&mut self.days, 7),
+            Unit::Day => (&mut self.days, 1),
+            Unit::Hour => (&mut self.hours, 1),
+            Unit::Minute => (&mut self.minutes, 1),
+            Unit::Second => (&mut self.seconds, 1),
+        };
+        *field = field.checked_add(whole.checked_mul(factor)?)?;
+        let Some((digits, negative)) = digits else {
+            return Some(());
+        };
+        let sign = if negative { -1.0 } else { 1.0 };
+        let mut rest = sign * fraction(digits)? * unit.seconds()? as f64;
+        rest = (rest * 1e9).round() / 1e9;
+        for (field, seconds) in [(&mut self.hours, 3_600.0), (&mut self.minutes, 60.0), (&mut self.seconds, 1.0)] {
+            let whole = (rest / seconds).trunc();
+            *field = field.checked_add(whole as i64)?;
+            rest -= whole * seconds;
+        }
+        self.sec_fraction += rest;
+        if self.sec_fraction.abs() >= 1.0 {
+            let carry = self.sec_fraction.trunc();
+            self.seconds = self.seconds.checked_add(carry as i64)?;
+            self.sec_fraction -= carry;
+        }
+        Some(())
+    }
+}
+
+/// Parses a duration, in any of the forms:
+///
+///   ISO 8601                 P1Y2M10DT2H30M, PT0.5S, P3W, -P1D
+///   ISO 8601 alternative     P0001-02-10T02:30:00, P00010210T023000
+///   PostgreSQL               1 year 2 mons 10 days 02:30:00, 1 day ago
+///   compact                  1h30m, 90s, 2d 4h, 1.5h
+///
+/// Only the last unit of the ISO forms may have a fraction, and years and
+/// months can't have one at all.
+///
+/// parse_duration('P1Y2M10DT2H30M') #=> {:years=>1, :months=>2, :days=>10, :hours=>2, :minutes=>30}
+/// parse_duration('1h30m')          #=> {:hours=>1, :minutes=>30}
+pub fn parse_duration(input: &str) -> crate::Result<CalendarDuration> {
+    lazy_static! {
+        static ref ISO_RE: Regex = RegexBuilder::new(r"
+            \A\s*([-+])?P
+            (?:([0-9]+(?:[.,][0-9]+)?)Y)?
+            (?:([0-9]+(?:[.,][0-9]+)?)M)?
+            (?:([0-9]+(?:[.,][0-9]+)?)W)?
+            (?:([0-9]+(?:[.,][0-9]+)?)D)?
+            (?:T
+                (?:([0-9]+(?:[.,][0-9]+)?)H)?
+                (?:([0-9]+(?:[.,][0-9]+)?)M)?
+                (?:([0-9]+(?:[.,][0-9]+)?)S)?
+            )?\s*\z")
+            .case_insensitive(true)
+            .ignore_whitespace(true)
+            .build()
+            .unwrap();
+        static ref ALTERNATIVE_RE: Regex = RegexBuilder::new(r"
+            \A\s*([-+])?P
+            (?:([0-9]{4})-([0-9]{2})-([0-9]{2})(?:T([0-9]{2}):([0-9]{2}):([0-9]{2})(?:[.,]([0-9]+))?)?
+              | ([0-9]{4})([0-9]{2})([0-9]{2})(?:T([0-9]{2})([0-9]{2})([0-9]{2})(?:[.,]([0-9]+))?)?)
+            \s*\z")
+            .case_insensitive(true)
+            .ignore_whitespace(true)
+            .build()
+            .unwrap();
+    }
+    let invalid = |span| ParseError::new(ErrorKind::InvalidDuration).with_input(input).with_span(span);
+
+    let duration = if let Some(caps) = ISO_RE.captures(input) {
+        iso_duration(&caps).ok_or_else(|| invalid(None))?
+    } else if let Some(caps) = ALTERNATIVE_RE.captures(input) {
+        alternative_duration(&caps).map_err(|err| err.with_input(input))?
+    } else {
+        units_duration(input).map_err(|at| invalid(Some(at..input.len())))?
+    };
+    Ok(duration)
+}
+
+fn iso_duration(caps: &Captures) -> Option<CalendarDuration> {
+    let units = [Unit::Year, Unit::Month, Unit::Week, Unit::Day, Unit::Hour, Unit::Minute, Unit::Second];
+    let given: Vec<(Unit, &str)> = units.iter().zip(2..).filter_map(|(unit, i)| Some((*unit, caps.get(i)?.as_str()))).collect();
+    // "P" and "PT" alone are no duration
+    let last = given.len().checked_sub(1)?;
+    let mut duration = CalendarDuration::default();
+    for (i, (unit, value)) in given.into_iter().enumerate() {
+        if value.contains(['.', ',']) && i != last {
+            return None;
+        }
+        duration.add_number(unit, value)?;
+    }
+    signed(duration, caps.get(1).map(|x| x.as_str()))
+}
+
+fn alternative_duration(caps: &Captures) -> crate::Result<CalendarDuration> {
+    let offset = if caps.get(2).is_some() { 2 } else { 9 };
+    let num = |i: usize, max: i64| -> crate::Result<i64> {
+        let Some(value) = caps.get(offset + i) else {
+            return Ok(0);
+        };
+        let value: i64 = value.as_str().parse().map_err(|_| ParseError::new(ErrorKind::InvalidDuration))?;
+        let field = [Unit::Year, Unit::Month, Unit::Day, Unit::Hour, Unit::Minute, Unit::Second][i].field();
+        if value > max {
+            return Err(ParseError::out_of_range(ErrorKind::FieldOutOfRange, field, value, Some(0..=max)));
+        }
+        Ok(value)
+    };
+    let mut duration = CalendarDuration {
+        years: num(0, 9999)?,
+        months: num(1, 12)?,
+        days: num(2, 30)?,
+        hours: num(3, 24)?,
+        minutes: num(4, 59)?,
+        seconds: num(5, 59)?,
+        sec_fraction: 0.0,
+    };
+    if let Some(digits) = caps.get(offset + 6) {
+        duration.sec_fraction = fraction(digits.as_str()).ok_or_else(|| ParseError::new(ErrorKind::InvalidDuration))?;
+    }
+    signed(duration, caps.get(1).map(|x| x.as_str()))
+        .ok_or_else(|| ParseError::new(ErrorKind::InvalidDuration))
+}
+
+// The PostgreSQL and compact forms: numbers with units, a clock time for
+// the hours, minutes and seconds, and "ago". Fails with the offset of the
+// text that isn't part of a duration.
+fn units_duration(input: &str) -> Result<CalendarDuration, usize> {
+    lazy_static! {
+        static ref PART_RE: Regex = RegexBuilder::new(r"
+            \A[\s,]*(?:
+                ([-+]?[0-9]+(?:[.,][0-9]+)?)\s*
+                (years?|yrs?|y|months?|mons?|mo|weeks?|wks?|w|days?|d|hours?|hrs?|h|minutes?|mins?|m|seconds?|secs?|s)
+              | ([-+])?([0-9]+):([0-9]{2})(?::([0-9]{2})(?:[.,]([0-9]+))?)?
+              | (ago)
+            )")
+            .case_insensitive(true)
+            .ignore_whitespace(true)
+            .build()
+            .unwrap();
+    }
+    let mut duration = CalendarDuration::default();
+    let (mut at, mut parts, mut ago) = (0, 0, false);
+    while !input[at..].trim().is_empty() {
+        let caps = PART_RE.captures(&input[at..]).ok_or(at)?;
+        let end = at + caps.get(0).map_or(0, |x| x.end());
+        // "1 mon" but not "1 monday", while "1h30m" runs on
+        if input[end..].starts_with(char::is_alphabetic) || ago {
+            return Err(at);
+        }
+        if caps.get(8).is_some() {
+            if parts == 0 {
+                return Err(at);
+            }
+            ago = true;
+        } else if let Some(number) = caps.get(1) {
+            let unit = match caps[2].to_lowercase().as_str() {
+                "y" | "yr" | "yrs" | "year" | "years" => Unit::Year,
+                "mo" | "mon" | "mons" | "month" | "months" => Unit::Month,
+                "w" | "wk" | "wks" | "week" | "weeks" => Unit::Week,
+                "d" | "day" | "days" => Unit::Day,
+                "h" | "hr" | "hrs" | "hour" | "hours" => Unit::Hour,
+                "m" | "min" | "mins" | "minute" | "minutes" => Unit::Minute,
+                _ => Unit::Second,
+            };
+            duration.add_number(unit, number.as_str()).ok_or(at)?;
+        } else {
+            let mut clock = CalendarDuration::default();
+            for (i, unit) in [(4, Unit::Hour), (5, Unit::Minute), (6, Unit::Second)] {
+                let value = caps.get(i).map_or(Some(0), |x| x.as_str().parse::<i64>().ok()).ok_or(at)?;
+                clock.add(unit, value, None).ok_or(at)?;
+            }
+            if let Some(digits) = caps.get(7) {
+                clock.sec_fraction = fraction(digits.as_str()).ok_or(at)?;
+            }
+            let clock = signed(clock, caps.get(3).map(|x| x.as_str())).ok_or(at)?;
+            for (unit, value) in [(Unit::Hour, clock.hours), (Unit::Minute, clock.minutes), (Unit::Second, clock.seconds)] {
+                duration.add(unit, value, None).ok_or(at)?;
+            }
+            duration.sec_fraction += clock.sec_fraction;
+        }
+        parts += 1;
+        at = end;
+    }
+    if parts == 0 {
+        return Err(0);
+    }
+    match ago {
+        true => duration.negated().ok_or(0),
+        false => Ok(duration),
+    }
+}
+
+fn signed(duration: CalendarDuration, sign: Option<&str>) -> Option<CalendarDuration> {
+    match sign {
+        Some("-") => duration.negated(),
+        _ => Some(duration),
+    }
+}
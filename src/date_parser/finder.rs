@@ -0,0 +1,203 @@
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+
+use crate::DateTime;
+use super::candidates::plausible;
+use super::parse::{date_parse_traced, YearPivot};
+use super::spans::{FieldSpan, Trace};
+
+static DAY_NAMES: [&str; 7] = ["sunday", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday"];
+static MONTH_NAMES: [&str; 12] = ["january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november", "december"];
+
+/// A date or time found in text by `find_dates`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DateMatch {
+    pub start: usize,
+    pub end: usize,
+    pub datetime: DateTime,
+}
+
+impl DateMatch {
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// Iterator over the dates in a text, returned by `find_dates`.
+#[derive(Debug, Clone)]
+pub struct FindDates<'a> {
+    text: &'a str,
+    scan: String,
+    pos: usize,
+}
+
+/// Scans prose for dates and times, yielding each one with its byte range
+/// in the text and its fields, read by `date_parse` with `comp`. Unlike
+/// `date_parse`, which merges whatever it finds into one date, every date
+/// comes on its own, along with the time, day of the week and zone right
+/// next to it:
+///
+/// find_dates('shipped Jan 3, delivered Fri Jan 7 at 10:00')
+///     #=> [{:range=>8...13, :mon=>1, :mday=>3},
+///          {:range=>25...43, :wday=>5, :mon=>1, :mday=>7, :hour=>10, :min=>0}]
+///
+/// Bare numbers, such as "order 12", and words that only start like the
+/// name of a month or day, such as "decided", are not taken for dates.
+pub fn find_dates(text: &str) -> FindDates<'_> {
+    FindDates { text, scan: without_full_stops(text), pos: 0 }
+}
+
+// A full stop right after a number ends the sentence, and would otherwise
+// be read as the decimal point of "20250312." by parse_ddd. It is blanked
+// out, keeping the offsets in the text as they are.
+fn without_full_stops(text: &str) -> String {
+    lazy_static! {
+        static ref FULL_STOP_RE: Regex = Regex::new(r"\d(\.)(?:\s|\z)").unwrap();
+    }
+    let mut scan = text.to_owned();
+    for caps in FULL_STOP_RE.captures_iter(text) {
+        let stop = caps.get(1).unwrap().range();
+        scan.replace_range(stop, " ");
+    }
+    scan
+}
+
+// Each date is looked for in a window of the text from the current
+// position, as parsing the whole rest of the text for every date would
+// make a scan quadratic in its length. A group ending within MARGIN of the
+// end of the window may go on past it, so it is looked for again from its
+// start, or in a window twice as large when it starts the window.
+const WINDOW: usize = 256;
+const MARGIN: usize = 64;
+
+impl Iterator for FindDates<'_> {
+    type Item = DateMatch;
+
+    fn next(&mut self) -> Option<DateMatch> {
+        let mut size = WINDOW;
+        while self.pos < self.text.len() {
+            let end = window_end(&self.scan, self.pos + size);
+            let window = &self.scan[self.pos..end];
+            let whole = end == self.scan.len();
+            let Some((range, anchored)) = first_group(window) else {
+                if whole {
+                    return None;
+                }
+                self.pos = window_end(&self.scan, self.pos + window.len() - MARGIN);
+                continue;
+            };
+            if !whole && range.end + MARGIN > window.len() {
+                match range.start {
+                    0 => size = size.saturating_mul(2),
+                    start => self.pos += start,
+                }
+                continue;
+            }
+            size = WINDOW;
+            let start = self.pos + range.start;
+            self.pos += range.end;
+            if !anchored || !names_are_words(&self.scan[start..self.pos]) {
+                continue;
+            }
+            if let Some(found) = read(&self.scan, start..self.pos) {
+                return Some(found);
+            }
+        }
+        None
+    }
+}
+
+// The end of a window reaching to +at+, moved on to the next white space so
+// that it doesn't cut a word or number in two.
+fn window_end(text: &str, at: usize) -> usize {
+    let mut at = at.min(text.len());
+    while !text.is_char_boundary(at) {
+        at += 1;
+    }
+    text[at..].find(char::is_whitespace).map_or(text.len(), |i| at + i)
+}
+
+// The sub-parsers of the date_parse pipeline each take the first match in
+// the whole string, and only one date sub-parser runs, so the spans they
+// report may be far apart and leave out a date that comes earlier. The
+// first group is found by grouping the spans that touch, then looking for
+// an earlier one in the text before it.
+fn first_group(text: &str) -> Option<(Range<usize>, bool)> {
+    let mut trace = Some(Trace::new(text));
+    date_parse_traced(text, None, None, &mut trace);
+    let mut spans = trace.map(|x| x.spans).unwrap_or_default();
+    spans.retain(|x| x.end > x.start);
+    spans.sort_by_key(|x| (x.start, x.end));
+    let first = spans.first()?;
+
+    let mut range = first.range();
+    let mut anchored = anchor(text, first);
+    for span in &spans[1..] {
+        if span.start > range.end && !joined(&text[range.end..span.start]) {
+            break;
+        }
+        range.end = range.end.max(span.end);
+        anchored |= anchor(text, span);
+    }
+
+    match first_group(&text[..range.start]) {
+        Some(earlier) => Some(earlier),
+        None => Some((range, anchored)),
+    }
+}
+
+// Text between a date and its time or zone, as in "Jan 7 at 10:00".
+fn joined(gap: &str) -> bool {
+    lazy_static! {
+        static ref JOINED_RE: Regex = RegexBuilder::new(r"\A[\s,]*(?:(?:at|on|@)[\s,]*)?\z")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+    }
+    JOINED_RE.is_match(gap)
+}
+
+// Spans of sub-parsers that read a date or time on their own, as opposed to
+// a bare number or month name, which is most often something else in prose.
+fn anchor(text: &str, span: &FieldSpan) -> bool {
+    match span.parser {
+        "parse_mon" | "parse_mday" | "parse_year" | "parse_frag" | "parse_bc" => false,
+        // 20241018, but not 1205
+        "parse_ddd" => text[span.range()].chars().filter(char::is_ascii_digit).count() >= 8,
+        _ => true,
+    }
+}
+
+// The names of months and days in +text+ are whole words or abbreviations
+// of them, not the start of "decided" or "monitor".
+fn names_are_words(text: &str) -> bool {
+    lazy_static! {
+        static ref WORD_RE: Regex = Regex::new(r"[[:alpha:]]+").unwrap();
+    }
+    WORD_RE.find_iter(text).all(|word| {
+        let word = word.as_str().to_lowercase();
+        let names = DAY_NAMES.iter().chain(MONTH_NAMES.iter());
+        let mut prefixed = names.filter(|name| word.get(..3).is_some_and(|x| name.starts_with(x))).peekable();
+        prefixed.peek().is_none() || prefixed.any(|name| name.starts_with(word.as_str()) || (word == "sept"))
+    })
+}
+
+// Parses the group on its own. A word after the time that isn't a known
+// zone, like "on" in "10:00 on", is left out, as is a trailing comma.
+fn read(text: &str, mut range: Range<usize>) -> Option<DateMatch> {
+    range.end = range.start + text[range.clone()].trim_end_matches([',', ' ']).len();
+    let pivot = Some(YearPivot::default());
+    let (mut datetime, _) = date_parse_traced(&text[range.clone()], pivot, None, &mut None);
+    if let (Some(zone), None) = (&datetime.zone, datetime.offset) {
+        if let Some(stripped) = text[range.clone()].strip_suffix(zone.as_str()) {
+            range.end = range.start + stripped.trim_end().len();
+            datetime = date_parse_traced(&text[range.clone()], pivot, None, &mut None).0;
+        }
+    }
+    if !plausible(&datetime) {
+        return None;
+    }
+    Some(DateMatch { start: range.start, end: range.end, datetime })
+}
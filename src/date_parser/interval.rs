@@ -0,0 +1,214 @@
+use std::ops::Range;
+
+use chrono::{DateTime, FixedOffset};
+use regex::{Regex, RegexBuilder};
+
+use crate::{ErrorKind, ParseError};
+use super::duration::{parse_duration, CalendarDuration};
+use super::strict::iso8601;
+use super::time::{has_date, make_week_time, LocalZone};
+use super::ParseOptions;
+
+/// A time interval, as read by `iso8601_interval` and `parse_range`. The
+/// duration is the one written in the input, for the `start/duration` and
+/// `duration/end` forms of ISO 8601.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+    pub duration: Option<CalendarDuration>,
+}
+
+/// A repeating ISO 8601 time interval, as read by
+/// `iso8601_repeating_interval`: the first interval, or the last one for
+/// the `duration/end` form, and the number of intervals, `None` when there
+/// is no end to them (`R/`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RepeatingInterval {
+    pub repetitions: Option<u32>,
+    pub interval: Interval,
+    backward: bool,
+}
+
+impl RepeatingInterval {
+    /// The intervals one after the other, going back from the end for the
+    /// `duration/end` form. The n-th interval is n durations away from the
+    /// first, so monthly intervals from January 31st start on the last day
+    /// of each month, and intervals given by their start and end are all
+    /// as long as the first.
+    pub fn intervals(&self) -> Intervals {
+        Intervals { repeating: *self, index: 0 }
+    }
+
+    fn nth(&self, n: u32) -> Option<Interval> {
+        let Interval { start, end, duration } = self.interval;
+        let (start, end) = match (duration, self.backward) {
+            (Some(duration), false) => (duration.scaled(n.into())?.since(start)?, duration.scaled(i64::from(n) + 1)?.since(start)?),
+            (Some(duration), true) => (duration.scaled(i64::from(n) + 1)?.ago(end)?, duration.scaled(n.into())?.ago(end)?),
+            (None, backward) => {
+                let length = end.signed_duration_since(start);
+                let (from, to) = (length.checked_mul(i32::try_from(n).ok()?)?, length.checked_mul(i32::try_from(n).ok()? + 1)?);
+                match backward {
+                    true => (end.checked_sub_signed(to)?, end.checked_sub_signed(from)?),
+                    false => (start.checked_add_signed(from)?, start.checked_add_signed(to)?),
+                }
+            }
+        };
+        Some(Interval { start, end, duration })
+    }
+}
+
+/// Iterator over the intervals of a `RepeatingInterval`, returned by
+/// `RepeatingInterval::intervals`. It ends early when an interval is out
+/// of range.
+#[derive(Debug, Clone)]
+pub struct Intervals {
+    repeating: RepeatingInterval,
+    index: u32,
+}
+
+impl Iterator for Intervals {
+    type Item = Interval;
+
+    fn next(&mut self) -> Option<Interval> {
+        if self.repeating.repetitions.is_some_and(|x| self.index >= x) {
+            return None;
+        }
+        let interval = self.repeating.nth(self.index)?;
+        self.index = self.index.checked_add(1)?;
+        Some(interval)
+    }
+}
+
+/// Parses +date+ as an ISO 8601 time interval given by its start and end
+/// (`2024-01-01/2024-02-01`), start and duration (`2024-01-01/P1M`) or
+/// duration and end (`P1M/2024-02-01`). The start and end are read like
+/// `time::iso8601` does, missing upper components taken from the current
+/// time, except that an end leaving out upper components of the start, as
+/// in `2024-02-15/20` or `2024-02-15T09:00/17:00`, takes them from the
+/// start, along with its zone when it has none.
+///
+/// iso8601_interval('2024-02-15T09:00Z/20T17:00')
+///     #=> 2024-02-15 09:00:00 UTC..2024-02-20 17:00:00 UTC
+/// iso8601_interval('2024-01-01/P1M') #=> 2024-01-01 00:00:00..2024-02-01 00:00:00 (local)
+pub fn iso8601_interval(date: &str) -> crate::Result<Interval> {
+    iso8601_interval_with_options(date, &ParseOptions::new())
+}
+
+/// Same as `iso8601_interval`, with the time missing components are taken
+/// from, and the zone (and its DST policy) times without a zone are taken
+/// in, of +options+.
+pub fn iso8601_interval_with_options(date: &str, options: &ParseOptions) -> crate::Result<Interval> {
+    let local = LocalZone::from_options(options);
+    let now = options.now.unwrap_or_else(|| local.now());
+    interval(date, 0, now, &local).map(|(interval, _)| interval)
+}
+
+/// Parses +date+ as an ISO 8601 repeating interval, `R` and the number of
+/// intervals, if any, before an interval read like `iso8601_interval` does.
+///
+/// iso8601_repeating_interval('R5/2024-01-01T00:00Z/P1D')
+///     #=> 5 intervals of a day from 2024-01-01 00:00:00 UTC
+/// iso8601_repeating_interval('R/P1W/2024-03-01T00:00Z')
+///     #=> weeks back from 2024-03-01 00:00:00 UTC
+pub fn iso8601_repeating_interval(date: &str) -> crate::Result<RepeatingInterval> {
+    iso8601_repeating_interval_with_options(date, &ParseOptions::new())
+}
+
+/// Same as `iso8601_repeating_interval`, with the time missing components
+/// are taken from, and the zone (and its DST policy) times without a zone
+/// are taken in, of +options+.
+pub fn iso8601_repeating_interval_with_options(date: &str, options: &ParseOptions) -> crate::Result<RepeatingInterval> {
+    lazy_static! {
+        static ref REPEATING_RE: Regex = Regex::new(r"\A\s*R([0-9]*)/").unwrap();
+    }
+    let caps = REPEATING_RE.captures(date)
+        .ok_or_else(|| ParseError::invalid_format(date, "iso8601 repeating interval"))?;
+    let repetitions = match &caps[1] {
+        "" => None,
+        digits => Some(digits.parse::<u32>().map_err(|_| {
+            ParseError::out_of_range(ErrorKind::FieldOutOfRange, "repetitions", digits, Some(0..=u32::MAX.into()))
+                .with_input(date)
+                .with_span(caps.get(1).map(|x| x.range()))
+        })?),
+    };
+    let local = LocalZone::from_options(options);
+    let now = options.now.unwrap_or_else(|| local.now());
+    let (interval, backward) = interval(date, caps[0].len(), now, &local)?;
+    Ok(RepeatingInterval { repetitions, interval, backward })
+}
+
+// Reads the interval in the part of +date+ from +at+, along with whether
+// it is given by its duration and end.
+fn interval(date: &str, at: usize, now: DateTime<FixedOffset>, local: &LocalZone) -> crate::Result<(Interval, bool)> {
+    let invalid = |span: Option<Range<usize>>| ParseError::invalid_format(date, "iso8601 interval").with_span(span);
+    let (first, second) = date[at..].split_once('/').ok_or_else(|| invalid(None))?;
+    let (first_span, second_span) = (at..at + first.len(), at + first.len() + 1..date.len());
+
+    let duration = |part: &str, span: &Range<usize>| {
+        part.trim_start().starts_with('P').then(|| {
+            parse_duration(part)
+                .map_err(|_| ParseError::new(ErrorKind::InvalidDuration).with_input(date).with_span(Some(span.clone())))
+        })
+    };
+    let point = |d: Option<crate::DateTime>, span: &Range<usize>, now| {
+        let d = d.ok_or_else(|| invalid(Some(span.clone())))?;
+        make_week_time(date, &d, d.year, now, local).map_err(|err| err.with_span(Some(span.clone())))
+    };
+    let unrepresentable = |duration: CalendarDuration| {
+        ParseError::out_of_range(ErrorKind::UnrepresentableYear, "year", duration, None).with_input(date)
+    };
+
+    match (duration(first, &first_span), duration(second, &second_span)) {
+        (None, None) => {
+            let start_fields = iso8601(first).filter(has_date);
+            let start = point(start_fields.clone(), &first_span, now)?;
+            let end_fields = start_fields.and_then(|start| end_fields(second, &start));
+            let end = point(end_fields, &second_span, start)?;
+            Ok((Interval { start, end, duration: None }, false))
+        }
+        (None, Some(duration)) => {
+            let duration = duration?;
+            let start = point(iso8601(first).filter(has_date), &first_span, now)?;
+            let end = duration.since(start).ok_or_else(|| unrepresentable(duration))?;
+            Ok((Interval { start, end, duration: Some(duration) }, false))
+        }
+        (Some(duration), None) => {
+            let duration = duration?;
+            let end = point(iso8601(second).filter(has_date), &second_span, now)?;
+            let start = duration.ago(end).ok_or_else(|| unrepresentable(duration))?;
+            Ok((Interval { start, end, duration: Some(duration) }, true))
+        }
+        (Some(_), Some(_)) => Err(invalid(None)),
+    }
+}
+
+// The fields of the end of an interval, which may leave out the upper
+// components of +start+, as in "2024-02-15/20", "2024-02-15/03-01" and
+// "2024-02-15T09:00/17:00", to be completed from the start. The zone is
+// that of the start when it has none.
+fn end_fields(text: &str, start: &crate::DateTime) -> Option<crate::DateTime> {
+    lazy_static! {
+        static ref ABBREVIATED_RE: Regex = RegexBuilder::new(r"\A\s*(?:([0-9]{2})-)?([0-9]{2})((?:t.*)?)\z")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+    }
+    // The truncated forms of iso8601, "--03-01" and "---20", read the day
+    // and month without a year.
+    let mut d = match ABBREVIATED_RE.captures(text).filter(|_| start.mday.is_some()) {
+        Some(caps) => {
+            let day = match caps.get(1) {
+                Some(mon) => format!("--{}-{}", mon.as_str(), &caps[2]),
+                None => format!("---{}", &caps[2]),
+            };
+            iso8601(&(day + &caps[3]))?
+        }
+        None => iso8601(text.trim_start().strip_prefix(['T', 't']).unwrap_or(text))?,
+    };
+    if d.zone.is_none() {
+        d.zone = start.zone.clone();
+        d.offset = start.offset;
+    }
+    Some(d)
+}
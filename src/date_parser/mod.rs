@@ -1,14 +1,86 @@
+mod candidates;
+mod date;
+mod duration;
+mod finder;
+mod interval;
+mod options;
 mod parse;
+mod range;
+mod relative;
+mod spans;
+mod strptime;
+mod strftime;
+mod strict;
 mod zonetab;
 pub mod time;
 #[cfg(test)]
 mod test_parse;
+#[cfg(test)]
+mod test_candidates;
+#[cfg(test)]
+mod test_date;
+#[cfg(test)]
+mod test_duration;
+#[cfg(test)]
+mod test_finder;
+#[cfg(test)]
+mod test_interval;
+#[cfg(test)]
+mod test_range;
+#[cfg(test)]
+mod test_spans;
+#[cfg(test)]
+mod test_no_panic;
+#[cfg(test)]
+mod test_strptime;
+#[cfg(test)]
+mod test_strict;
 
+pub use candidates::parse_candidates;
+pub use candidates::Candidate;
+pub use date::parse_date;
+pub use date::parse_date_with_today;
+pub use date::Date;
+pub use duration::parse_duration;
+pub use duration::CalendarDuration;
+pub use finder::find_dates;
+pub use finder::DateMatch;
+pub use finder::FindDates;
+pub use interval::iso8601_interval;
+pub use interval::iso8601_interval_with_options;
+pub use interval::iso8601_repeating_interval;
+pub use interval::iso8601_repeating_interval_with_options;
+pub use interval::Interval;
+pub use interval::Intervals;
+pub use interval::RepeatingInterval;
+pub use options::ParseOptions;
 pub use parse::date_parse;
 pub use parse::date_parse2;
+pub use parse::date_parse_with_year_pivot;
+pub use parse::YearPivot;
+pub use parse::DateOrder;
+pub use parse::date_parse_with_options;
+pub use range::date_parse_range;
+pub use range::parse_range;
+pub use range::parse_range_with_options;
+pub use spans::date_parse_with_spans;
+pub use spans::FieldSpan;
+pub use strptime::date_strptime;
+pub use strptime::date_strptime2;
+pub use strftime::strftime;
+pub use strftime::to_jisx0301;
+pub use strftime::to_jisx0301_time;
+pub use strict::iso8601;
+pub use strict::rfc3339;
+pub use strict::xmlschema;
+pub use strict::rfc2822;
+pub use strict::httpdate;
+pub use strict::jisx0301;
+pub use strict::rfc9557;
+pub(crate) use zonetab::tz_variable_zone;
 
 use serde::Serialize;
-#[derive(Debug, Default, Serialize, std::cmp::PartialEq)]
+#[derive(Debug, Default, Clone, Serialize, std::cmp::PartialEq)]
 pub struct DateTime {
     pub hour: Option<u32>,
     pub min: Option<u32>,
@@ -22,9 +94,24 @@ pub struct DateTime {
     pub cwyear: Option<i32>,
     pub cweek: Option<u32>,
     pub cwday: Option<u32>,
+    pub wnum0: Option<u32>,
+    pub wnum1: Option<u32>,
+    pub seconds: Option<f64>,
     pub offset: Option<i32>,
     pub zone: Option<String>,
     pub bc: bool,
     pub comp: Option<bool>,
+    pub ixdtf: Option<Ixdtf>,
+}
+
+/// The RFC 9557 suffixes following a timestamp, as in
+/// `2024-10-18T09:30:00+09:00[!Asia/Tokyo][u-ca=japanese]`. A critical
+/// suffix is one marked with `!`.
+#[derive(Debug, Default, Clone, Serialize, std::cmp::PartialEq)]
+pub struct Ixdtf {
+    pub zone: Option<String>,
+    pub zone_critical: bool,
+    pub calendar: Option<String>,
+    pub calendar_critical: bool,
 }
 
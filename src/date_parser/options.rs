@@ -0,0 +1,110 @@
+use chrono::{DateTime, FixedOffset};
+
+use super::time::{DstPolicy, WeekdayPolicy};
+use crate::ParseError;
+use super::{DateOrder, YearPivot};
+
+type WarningFn = Box<dyn Fn(&ParseError) + Send + Sync>;
+
+/// Options for `time::parse_with_options`, built up from the defaults of
+/// `time::parse`:
+///
+/// ```
+/// use rb_date_parser::{parse_with_options, ParseOptions, YearPivot};
+///
+/// let options = ParseOptions::new()
+///     .zone(chrono_tz::America::New_York)
+///     .year_pivot(YearPivot::ClosestTo(2030));
+/// let t = parse_with_options("Dec 31 75 10:00", &options).unwrap();
+/// assert_eq!(t.to_rfc3339(), "2075-12-31T10:00:00-05:00");
+/// ```
+#[derive(Default)]
+pub struct ParseOptions {
+    pub(crate) now: Option<DateTime<FixedOffset>>,
+    pub(crate) year_fn: Option<Box<dyn Fn(i32) -> i32 + Send + Sync>>,
+    pub(crate) year_pivot: YearPivot,
+    pub(crate) zone: Option<chrono_tz::Tz>,
+    pub(crate) dst_policy: DstPolicy,
+    pub(crate) date_order: Option<DateOrder>,
+    pub(crate) weekday_policy: WeekdayPolicy,
+    pub(crate) on_warning: Option<WarningFn>,
+}
+
+impl ParseOptions {
+    pub fn new() -> ParseOptions {
+        ParseOptions::default()
+    }
+
+    /// The time missing upper components are taken from, the current time
+    /// by default.
+    pub fn now(mut self, now: DateTime<FixedOffset>) -> ParseOptions {
+        self.now = Some(now);
+        self
+    }
+
+    /// Converts the parsed year, like the block of Ruby's `Time.parse`.
+    /// Two digit years are then left for it to complete, so the year pivot
+    /// doesn't apply.
+    pub fn year_fn<F>(mut self, year_fn: F) -> ParseOptions
+    where
+        F: Fn(i32) -> i32 + Send + Sync + 'static,
+    {
+        self.year_fn = Some(Box::new(year_fn));
+        self
+    }
+
+    /// How two digit years are completed, Ruby's 69 cutoff by default.
+    pub fn year_pivot(mut self, year_pivot: YearPivot) -> ParseOptions {
+        self.year_pivot = year_pivot;
+        self
+    }
+
+    /// The zone times without a zone are taken in, the system time zone by
+    /// default.
+    pub fn zone(mut self, zone: chrono_tz::Tz) -> ParseOptions {
+        self.zone = Some(zone);
+        self
+    }
+
+    /// How times in a DST gap or overlap of the zone are resolved.
+    pub fn dst_policy(mut self, dst_policy: DstPolicy) -> ParseOptions {
+        self.dst_policy = dst_policy;
+        self
+    }
+
+    /// The order of the fields of numeric dates such as 03/04/2024, Ruby's
+    /// reading by default.
+    pub fn date_order(mut self, date_order: DateOrder) -> ParseOptions {
+        self.date_order = Some(date_order);
+        self
+    }
+
+    /// What is done when the day of the week in the input doesn't match
+    /// the date, which is disregarded by default.
+    pub fn weekday_policy(mut self, weekday_policy: WeekdayPolicy) -> ParseOptions {
+        self.weekday_policy = weekday_policy;
+        self
+    }
+
+    /// Called with the problems that don't fail the parse, such as a day
+    /// of the week that doesn't match the date under `WeekdayPolicy::Warn`.
+    pub fn on_warning<F>(mut self, on_warning: F) -> ParseOptions
+    where
+        F: Fn(&ParseError) + Send + Sync + 'static,
+    {
+        self.on_warning = Some(Box::new(on_warning));
+        self
+    }
+
+    // The completion of two digit years: none when the year block takes
+    // them, and YearPivot::ClosestToNow relative to +now_year+.
+    pub(crate) fn year_pivot_at(&self, now_year: i32) -> Option<YearPivot> {
+        if self.year_fn.is_some() {
+            return None;
+        }
+        match self.year_pivot {
+            YearPivot::ClosestToNow => Some(YearPivot::ClosestTo(now_year)),
+            pivot => Some(pivot),
+        }
+    }
+}
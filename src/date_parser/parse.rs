@@ -1,7 +1,10 @@
 use phf::phf_map;
+use chrono::Datelike;
 use regex::{Regex, Captures, RegexBuilder};
 
 use crate::DateTime;
+use super::ParseOptions;
+use super::spans::Trace;
 
 const SPACE: &str = " ";
 const HAVE_ALPHA: u32 = 1<<0;
@@ -10,7 +13,7 @@ const HAVE_DASH: u32 = 1<<2;
 const HAVE_DOT: u32 = 1<<3;
 const HAVE_SLASH: u32 = 1<<4;
 
-fn check_classes(string: &str, classes: u32) -> bool {
+pub(super) fn check_classes(string: &str, classes: u32) -> bool {
     let mut class: u32 = 0;
     for i in string.chars() {
         if i.is_alphabetic() {
@@ -56,34 +59,72 @@ static MONTHS_HASH: phf::Map<&'static str, &'static str> = phf_map! {
     "dec" => "12",
     };
 
-fn gengo(c: char) -> i32 {
-    match c {
-        'M' => 1867,
-        'm' => 1867,
-        'T' => 1911,
-        't' => 1911,
-        'S' => 1925,
-        's' => 1925,
-        'H' => 1988,
-        'h' => 1988,
-        'R' => 2018,
-        'r' => 2018,
-        _ => 0,
-    }
+// The Japanese eras of JIS X 0301. Each era runs from its first day up to
+// the day before the next era starts. Meiji is only counted from
+// 1873-01-01 (M06.01.01), when Japan adopted the Gregorian calendar; the
+// standard does not cover the lunisolar dates before that.
+pub(super) struct Era {
+    pub initial: char,
+    pub full_width: char,
+    pub name: &'static str,
+    pub base: i32,
+    pub start: (i32, u32, u32),
 }
 
+pub(super) static ERAS: [Era; 5] = [
+    Era { initial: 'M', full_width: 'Ｍ', name: "明治", base: 1867, start: (1873, 1, 1) },
+    Era { initial: 'T', full_width: 'Ｔ', name: "大正", base: 1911, start: (1912, 7, 30) },
+    Era { initial: 'S', full_width: 'Ｓ', name: "昭和", base: 1925, start: (1926, 12, 25) },
+    Era { initial: 'H', full_width: 'Ｈ', name: "平成", base: 1988, start: (1989, 1, 8) },
+    Era { initial: 'R', full_width: 'Ｒ', name: "令和", base: 2018, start: (2019, 5, 1) },
+];
+
+// Looks up an era by its initial (M, T, S, H, R in either case, half or
+// full width) or by its kanji name.
+pub(super) fn era_by_name(name: &str) -> Option<&'static Era> {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => {
+            let c = c.to_uppercase().next()?;
+            ERAS.iter().find(|era| era.initial == c || era.full_width == c)
+        }
+        _ => ERAS.iter().find(|era| era.name == name),
+    }
+}
 
-fn day_num(date: &str) -> i32 {
+pub(super) fn day_num(date: &str) -> i32 {
     *DAYS_HASH.get(&date.to_lowercase()).unwrap_or(&0)
 }
 
-fn months_num(date: &str) -> &'static str {
+pub(super) fn months_num(date: &str) -> &'static str {
     MONTHS_HASH.get(&date.to_lowercase()).unwrap_or(&"01")
 }
 
+// Digit runs too long for the integer types saturate, so that they fail the
+// range checks later on instead of overflowing.
+fn int_i32(digits: &str) -> Option<i32> {
+    use std::num::IntErrorKind;
+    match digits.parse::<i32>() {
+        Ok(n) => Some(n),
+        Err(err) if *err.kind() == IntErrorKind::PosOverflow => Some(i32::MAX),
+        Err(err) if *err.kind() == IntErrorKind::NegOverflow => Some(i32::MIN),
+        Err(_) => None,
+    }
+}
+
+fn int_u32(digits: &str) -> Option<u32> {
+    use std::num::IntErrorKind;
+    match digits.parse::<u32>() {
+        Ok(n) => Some(n),
+        Err(err) if *err.kind() == IntErrorKind::PosOverflow => Some(u32::MAX),
+        Err(_) => None,
+    }
+}
+
 fn slice_number_part(string: &str) -> (&str, &str, &str) {
-    if let Some(index) = string.find(|c:char| !c.is_ascii_digit()) {
-        (&string[0..index], &string[index..=index], &string[(index+1)..])
+    if let Some((index, c)) = string.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        let next = index + c.len_utf8();
+        (&string[0..index], &string[index..next], &string[next..])
     } else {
         (string, "", "")
     }
@@ -98,8 +139,8 @@ fn slice_signed_number_part(string: &str) -> Option<(&str, &str, &str)> {
             ("", &string[index..])
         };
 
-        let (number_slice, remaining) = if let Some(index) = rest.find(|c:char| !c.is_ascii_digit()) {
-            (&rest[0..index], &rest[(index+1)..])
+        let (number_slice, remaining) = if let Some((index, c)) = rest.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+            (&rest[0..index], &rest[(index + c.len_utf8())..])
         } else {
             (rest, "")
         };
@@ -190,7 +231,9 @@ fn validate_and_set_date(datetime: &mut DateTime, yr: &Option<&str>, m: &Option<
             if !sign.is_empty() || digits.len() > 2 {
                 comp = Some(false);
             }
-            datetime.year = Some(format!("{}{}", sign, digits).parse::<i32>().unwrap());
+            if let Some(year) = int_i32(&format!("{}{}", sign, digits)) {
+                datetime.year = Some(year);
+            }
         }
     }
 
@@ -198,7 +241,7 @@ fn validate_and_set_date(datetime: &mut DateTime, yr: &Option<&str>, m: &Option<
         if let Some(cap) = DIGITS_REGEX.captures(&month_str) {
             if let Some(mtch) = cap.get(1) {
                 let  digits = mtch.as_str();
-                datetime.mon = Some(digits.parse::<u32>().unwrap());
+                datetime.mon = int_u32(digits);
             }
         }
     }
@@ -207,7 +250,7 @@ fn validate_and_set_date(datetime: &mut DateTime, yr: &Option<&str>, m: &Option<
         if let Some(cap) = DIGITS_REGEX.captures(&day_str) {
             if let Some(mtch) = cap.get(1) {
                 let  digits = mtch.as_str();
-                datetime.mday = Some(digits.parse::<u32>().unwrap());
+                datetime.mday = int_u32(digits);
             }
         }
     }
@@ -346,9 +389,9 @@ fn parse_iso_21(string: &mut String, datetime: &mut DateTime) -> bool {
     let mut matched = false;
     let result = ISO21_REGEX.replace(string, |caps: &Captures| {
         matched =  true;
-        let cwyear = caps.get(1).map(|x| x.as_str()).map(|x| x.parse::<i32>().unwrap());
-        let cweek = caps.get(2).map(|x| x.as_str()).map(|x| x.parse::<i32>().unwrap());
-        let cwday = caps.get(3).map(|x| x.as_str()).map(|x| x.parse::<i32>().unwrap());
+        let cwyear = caps.get(1).map(|x| x.as_str()).and_then(int_i32);
+        let cweek = caps.get(2).map(|x| x.as_str()).and_then(int_i32);
+        let cwday = caps.get(3).map(|x| x.as_str()).and_then(int_i32);
         if let Some(n) = cwyear {
             datetime.cwyear = Some(n);
         }
@@ -377,7 +420,7 @@ fn parse_iso_22(string: &mut String, datetime: &mut DateTime) -> bool {
     let mut matched = false;
     let result = ISO22_REGEX.replace(string, |caps: &Captures| {
         matched =  true;
-        let cwday = caps.get(1).map(|x| x.as_str()).map(|x| x.parse::<i32>().unwrap());
+        let cwday = caps.get(1).map(|x| x.as_str()).and_then(int_i32);
         if let Some(n) = cwday {
             datetime.cwday = Some(n as u32);
         }
@@ -399,8 +442,8 @@ fn parse_iso_23(string: &mut String, datetime: &mut DateTime) -> bool {
     let mut matched = false;
     let result = ISO23_REGEX.replace(string, |caps: &Captures| {
         matched =  true;
-        let mon = caps.get(1).map(|x| x.as_str()).map(|x| x.parse::<i32>().unwrap());
-        let day = caps.get(2).map(|x| x.as_str()).map(|x| x.parse::<i32>().unwrap());
+        let mon = caps.get(1).map(|x| x.as_str()).and_then(int_i32);
+        let day = caps.get(2).map(|x| x.as_str()).and_then(int_i32);
         if let Some(n) = mon {
             datetime.mon = Some(n as u32);
         }
@@ -425,8 +468,8 @@ fn parse_iso_24(string: &mut String, datetime: &mut DateTime) -> bool {
     let mut matched = false;
     let result = ISO24_REGEX.replace(string, |caps: &Captures| {
         matched =  true;
-        let mon = caps.get(1).map(|x| x.as_str()).map(|x| x.parse::<i32>().unwrap());
-        let day = caps.get(2).map(|x| x.as_str()).map(|x| x.parse::<i32>().unwrap());
+        let mon = caps.get(1).map(|x| x.as_str()).and_then(int_i32);
+        let day = caps.get(2).map(|x| x.as_str()).and_then(int_i32);
         if let Some(n) = mon {
             datetime.mon = Some(n as u32);
         }
@@ -460,8 +503,8 @@ fn parse_iso_25(string: &mut String, datetime: &mut DateTime) -> bool {
 
     let result = ISO25_REGEX2.replace(string, |caps: &Captures| {
         matched =  true;
-        let y = caps.get(1).map(|x| x.as_str()).map(|x| x.parse::<i32>().unwrap());
-        let d = caps.get(2).map(|x| x.as_str()).map(|x| x.parse::<i32>().unwrap());
+        let y = caps.get(1).map(|x| x.as_str()).and_then(int_i32);
+        let d = caps.get(2).map(|x| x.as_str()).and_then(int_i32);
         if let Some(n) = y {
             datetime.year = Some(n);
         }
@@ -495,7 +538,7 @@ fn parse_iso_26(string: &mut String, datetime: &mut DateTime) -> bool {
 
     let result = ISO26_REGEX2.replace(string, |caps: &Captures| {
         matched =  true;
-        let d = caps.get(1).map(|x| x.as_str()).map(|x| x.parse::<i32>().unwrap());
+        let d = caps.get(1).map(|x| x.as_str()).and_then(int_i32);
         if let Some(n) = d {
             datetime.yday = Some(n);
         }
@@ -534,7 +577,7 @@ fn parse_iso2(string: &mut String, datetime: &mut DateTime) -> bool {
 
 fn parse_jis(string: &mut String, datetime: &mut DateTime) -> bool {
     lazy_static! {
-        static ref JIS_REGEX: Regex = RegexBuilder::new(r"\b([mtshr])(\d+)\.(\d+)\.(\d+)")
+        static ref JIS_REGEX: Regex = RegexBuilder::new(r"\b([mtshr]|[ＭＴＳＨＲ]|明治|大正|昭和|平成|令和)(\d+)\.(\d+)\.(\d+)")
                         .case_insensitive(true)
                         .build()
                         .unwrap();
@@ -544,23 +587,23 @@ fn parse_jis(string: &mut String, datetime: &mut DateTime) -> bool {
         matched =  true;
         let ep = caps.get(1)
                                 .map(|x| x.as_str())
-                                .and_then(|x| x.chars().next())
-                                    .map(gengo)
+                                .and_then(era_by_name)
+                                    .map(|era| era.base)
                                 .unwrap_or_default();
 
         if let Some(x) = caps.get(2)
                 .map(|x| x.as_str())
-                .map(|x| x.parse::<i32>().unwrap())
-                .map(|x| x + ep) { datetime.year = Some(x); }
+                .and_then(int_i32)
+                .map(|x| x.saturating_add(ep)) { datetime.year = Some(x); }
 
         if let Some(x) = caps.get(3)
                 .map(|x| x.as_str())
-                .map(|x| x.parse::<u32>().unwrap()) { datetime.mon = Some(x); }
+                .and_then(int_u32) { datetime.mon = Some(x); }
 
 
         if let Some(x) = caps.get(4)
                 .map(|x| x.as_str())
-                .map(|x| x.parse::<u32>().unwrap()) { datetime.mday = Some(x); }
+                .and_then(int_u32) { datetime.mday = Some(x); }
 
 
         SPACE
@@ -638,7 +681,52 @@ fn parse_vms(string: &mut String, datetime: &mut DateTime) -> bool {
 }
 
 
-fn parse_sla(string: &mut String, datetime: &mut DateTime) -> bool {
+/// The order of the fields of numeric dates such as 03/04/2024 or
+/// 03.04.2024, where Ruby's own reading is day first when the year comes
+/// last (2024-04-03) and year first otherwise (03/04/24 is 2003-04-24).
+///
+/// A field with more than two digits, a sign or an apostrophe is always the
+/// year, and a date starting with the year is always read year, month, day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum DateOrder {
+    /// 03/04/2024 and 03/04/24 are March 4th, 03/04 is March 4th.
+    Mdy,
+    /// 03/04/2024 and 03/04/24 are April 3rd, 03/04 is April 3rd.
+    Dmy,
+    /// 03/04/05 is 2003-04-05, 03/04 is March 4th, and 03/04/2024 is
+    /// April 3rd as with Ruby.
+    Ymd,
+}
+
+// Puts the fields of a numeric date in the year, month, day order
+// validate_and_set_date takes them, following +order+. Without an order
+// they are passed as they come, which is Ruby's reading.
+fn order_date_fields<'a>(a: Option<&'a str>, b: Option<&'a str>, c: Option<&'a str>, order: Option<DateOrder>) -> (Option<&'a str>, Option<&'a str>, Option<&'a str>) {
+    let order = match order {
+        Some(order) => order,
+        None => return (a, b, c),
+    };
+    let is_year = |x: Option<&str>| x.is_some_and(|x| x.starts_with(['\'', '-']) || x.len() > 2);
+
+    if c.is_none() {
+        // validate_and_set_date reads two fields as month and day.
+        return match order {
+            DateOrder::Dmy => (b, a, None),
+            DateOrder::Mdy | DateOrder::Ymd => (a, b, None),
+        };
+    }
+    if is_year(a) || (is_year(b) && !is_year(c)) {
+        return (a, b, c);
+    }
+    match order {
+        DateOrder::Mdy => (c, a, b),
+        DateOrder::Dmy => (c, b, a),
+        DateOrder::Ymd if is_year(c) => (c, b, a),
+        DateOrder::Ymd => (a, b, c),
+    }
+}
+
+fn parse_sla(string: &mut String, datetime: &mut DateTime, order: Option<DateOrder>) -> bool {
     lazy_static! {
         static ref SLA_REGEX: Regex = RegexBuilder::new(r"
                             ('?-?\d+)/\s*('?\d+)(?:\D\s*('?-?\d+))?
@@ -651,9 +739,7 @@ fn parse_sla(string: &mut String, datetime: &mut DateTime) -> bool {
     let mut matched = false;
     let result = SLA_REGEX.replace(string, |caps: &Captures| {
         matched =  true;
-        let year = caps.get(1).map(|x| x.as_str());
-        let mon = caps.get(2).map(|x| x.as_str());
-        let day = caps.get(3).map(|x| x.as_str());
+        let (year, mon, day) = order_date_fields(caps.get(1).map(|x| x.as_str()), caps.get(2).map(|x| x.as_str()), caps.get(3).map(|x| x.as_str()), order);
         validate_and_set_date(datetime, &year, &mon, &day, false);
 
 
@@ -664,7 +750,7 @@ fn parse_sla(string: &mut String, datetime: &mut DateTime) -> bool {
 }
 
 
-fn parse_dot(string: &mut String, datetime: &mut DateTime) -> bool {
+fn parse_dot(string: &mut String, datetime: &mut DateTime, order: Option<DateOrder>) -> bool {
     lazy_static! {
         static ref DOT_REGEX: Regex = RegexBuilder::new(r"
                             ('?-?\d+)\.\s*('?\d+)\.\s*('?-?\d+)
@@ -677,9 +763,7 @@ fn parse_dot(string: &mut String, datetime: &mut DateTime) -> bool {
     let mut matched = false;
     let result = DOT_REGEX.replace(string, |caps: &Captures| {
         matched =  true;
-        let year = caps.get(1).map(|x| x.as_str());
-        let mon = caps.get(2).map(|x| x.as_str());
-        let day = caps.get(3).map(|x| x.as_str());
+        let (year, mon, day) = order_date_fields(caps.get(1).map(|x| x.as_str()), caps.get(2).map(|x| x.as_str()), caps.get(3).map(|x| x.as_str()), order);
         validate_and_set_date(datetime, &year, &mon, &day, false);
 
 
@@ -951,8 +1035,7 @@ fn parse_ddd(string: &mut String, datetime: &mut DateTime) -> bool {
             }
         }
         if let Some(s4) = s4 {
-            let frac_str = format!("0.{}", s4);
-            if let Ok(fraction) = frac_str.parse::<f64>() {
+            if let Some(fraction) = fraction(s4) {
                 datetime.sec_fraction = Some(fraction);
             }
         }
@@ -986,7 +1069,28 @@ fn parse_ddd(string: &mut String, datetime: &mut DateTime) -> bool {
 
 
 
-fn date_zone_to_diff(zone: &str) -> Option<i32> {
+// Same as date_zone_to_diff, but also resolves IANA zone names such as
+// Europe/Berlin, using the offset in effect at the parsed date and time.
+// The date has to be complete for that, a zone name alone doesn't tell
+// whether DST applies. Wall clock times in a DST overlap take the earlier
+// offset and the ones in a gap the offset before it.
+pub(super) fn date_zone_to_diff_at(zone: &str, datetime: &DateTime) -> Option<i32> {
+    use chrono::{LocalResult, Offset, TimeZone};
+
+    let tz = match super::zonetab::iana_zone(zone) {
+        Some(tz) => tz,
+        None => return date_zone_to_diff(zone),
+    };
+    let naive = chrono::NaiveDate::from_ymd_opt(datetime.year?, datetime.mon?, datetime.mday?)?
+        .and_hms_opt(datetime.hour.unwrap_or(0), datetime.min.unwrap_or(0), datetime.sec.unwrap_or(0))?;
+    let offset = match tz.offset_from_local_datetime(&naive) {
+        LocalResult::Single(offset) | LocalResult::Ambiguous(offset, _) => offset,
+        LocalResult::None => tz.offset_from_utc_datetime(&naive.checked_sub_signed(chrono::Duration::days(1))?),
+    };
+    Some(offset.fix().local_minus_utc())
+}
+
+pub(super) fn date_zone_to_diff(zone: &str) -> Option<i32> {
     lazy_static! {
         static ref STD_TIME_SUFFIX: Regex = RegexBuilder::new(r"(.*)\s*(?i:standard time)")
                                                 .case_insensitive(true)
@@ -1035,20 +1139,23 @@ fn date_zone_to_diff(zone: &str) -> Option<i32> {
         let (sign, rest) = zone_str.split_at(1);
         let sign =  if sign.starts_with('-') { -1 } else { 1 };
 
+        // Offsets are worked out in i64 so that long digit runs give an
+        // offset out of range rather than overflow.
+        let offset = |hours: i64, mins: i64, secs: i64| i32::try_from(sign * (hours * 3600 + mins * 60 + secs)).ok();
         let (hours_str, split_char, rest) = slice_number_part(rest);
-        let mut hours = hours_str.parse::<i32>().unwrap();
+        let mut hours = hours_str.parse::<i64>().unwrap_or(if hours_str.is_empty() { 0 } else { i64::MAX }).min(i64::from(i32::MAX));
         let mut  mins =  0;
         let mut  secs =  0;
 
         if split_char == ":" {
             let (mins_str, split_char, rest) = slice_number_part(rest);
-            let mins = mins_str.parse::<i32>().unwrap_or_default();
+            let mins = mins_str.parse::<i64>().unwrap_or_default().min(i64::from(i32::MAX));
             if split_char == ":" {
-                if let Ok(secs) = rest.parse::<i32>() {
-                    return Some(sign * (secs + 60*mins + 3600*hours));
+                if let Ok(secs) = rest.parse::<i64>() {
+                    return offset(hours, mins, secs.min(i64::from(i32::MAX)));
                 }
             }
-            return Some(sign * (60*mins + 3600*hours));
+            return offset(hours, mins, 0);
         }
 
         if split_char == "," || split_char == "." {
@@ -1060,21 +1167,22 @@ fn date_zone_to_diff(zone: &str) -> Option<i32> {
                     mins = fraction;
                 }
             }
-            return Some(sign * (hours * 3600 + (mins * 3600.0) as i32));
+            return offset(hours, 0, (mins * 3600.0) as i64);
         }
         let l = hours_str.len();
         if  l >= 2 {
+            let digits = |range: std::ops::Range<usize>| hours_str.get(range).and_then(|x| x.parse::<i64>().ok()).unwrap_or_default();
             if l >= 1 {
-                hours = hours_str[0..(2 - l % 2)].parse::<i32>().unwrap();
+                hours = digits(0..(2 - l % 2));
             }
             if l >= 3 {
-                mins = hours_str[(2 - l % 2)..(4 - l % 2)].parse::<i32>().unwrap();
+                mins = digits((2 - l % 2)..(4 - l % 2));
             }
             if l >= 5 {
-                secs = hours_str[(4 - l % 2)..(6 - l % 2)].parse::<i32>().unwrap();
+                secs = digits((4 - l % 2)..(6 - l % 2));
             }
         }
-        return Some(sign * (hours * 3600 + mins * 60 + secs));
+        return offset(hours, mins, secs);
     }
     None
 }
@@ -1092,7 +1200,7 @@ fn parse_year(string: &mut String, datetime: &mut DateTime) -> bool {
         matched =  true;
         if let Some(x) = caps.get(1)
                 .map(|x| x.as_str())
-                .map(|x| x.parse::<i32>().unwrap()) { datetime.year = Some(x); }
+                .and_then(int_i32) { datetime.year = Some(x); }
 
 
         SPACE
@@ -1114,7 +1222,7 @@ fn parse_mon(string: &mut String, datetime: &mut DateTime) -> bool {
         matched =  true;
         if let Some(x) = caps.get(1)
                 .map(|x| months_num(x.as_str()))
-                .map(|x| x.parse::<u32>().unwrap()) { datetime.mon = Some(x); }
+                .and_then(int_u32) { datetime.mon = Some(x); }
 
 
         SPACE
@@ -1136,7 +1244,7 @@ fn parse_mday(string: &mut String, datetime: &mut DateTime) -> bool {
         matched =  true;
         if let Some(x) = caps.get(1)
                 .map(|x| x.as_str())
-                .map(|x| x.parse::<u32>().unwrap()) { datetime.mday = Some(x); }
+                .and_then(int_u32) { datetime.mday = Some(x); }
 
 
         SPACE
@@ -1161,6 +1269,12 @@ fn parse_day(string: &mut String, datetime: &mut DateTime) -> bool {
     true
 }
 
+// The value of the digits after the decimal point, as in the "123" of
+// "10:00:05.123".
+pub(super) fn fraction(digits: &str) -> Option<f64> {
+    format!("0.{}", digits).parse::<f64>().ok()
+}
+
 fn parse_time(string: &mut String, datetime: &mut DateTime) -> bool {
     lazy_static! {
         static ref TIME_WITH_ZONE: Regex = RegexBuilder::new(r"
@@ -1233,8 +1347,7 @@ fn parse_time(string: &mut String, datetime: &mut DateTime) -> bool {
                     }
                 }
                 if let Some(fraction_str) = &time_caps.get(4) {
-                    let frac_str = format!("0.{}", fraction_str.as_str());
-                    if let Ok(fraction) = frac_str.parse::<f64>() {
+                    if let Some(fraction) = fraction(fraction_str.as_str()) {
                         datetime.sec_fraction = Some(fraction);
                     }
                 }
@@ -1258,6 +1371,38 @@ fn parse_time(string: &mut String, datetime: &mut DateTime) -> bool {
     true
 }
 
+// IANA zone names (Europe/Berlin), bare or in brackets as in
+// 2024-06-01T10:00[America/Sao_Paulo]. They are taken out before anything
+// else so that the other parsers don't read America/Montevideo as a Monday.
+// The zone names are matched on +input+, as the underscores in names such
+// as America/Sao_Paulo aren't among the characters date_chars keeps, and
+// the name is then blanked out of +string+.
+fn parse_iana_zone(input: &str, string: &mut String, datetime: &mut DateTime) -> bool {
+    lazy_static! {
+        static ref IANA_ZONE_REGEX: Regex = RegexBuilder::new(r"
+                \[([[:alpha:]]+(?:/[[:alnum:]_+-]+)+)\]
+            |
+                \b([[:alpha:]]+(?:/[[:alnum:]_+-]+)+)
+        ")
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let found = IANA_ZONE_REGEX.captures_iter(input).find_map(|caps| {
+        let name = caps.get(1).or_else(|| caps.get(2))?.as_str();
+        super::zonetab::iana_zone(name)?;
+        Some((caps.get(0)?.range(), name))
+    });
+    let Some((range, name)) = found else {
+        return false;
+    };
+    let (_, origin) = date_chars_mapped(input);
+    let position = |offset: usize| origin.iter().position(|&x| x >= offset).unwrap_or(string.len());
+    string.replace_range(position(range.start)..position(range.end), SPACE);
+    datetime.zone = Some(name.to_owned());
+    true
+}
+
 fn parse_bc(string: &mut String, datetime: &mut DateTime) -> bool {
     lazy_static! {
         static ref BC_REGEX: Regex = RegexBuilder::new(r"\b(bc\b|bce\b|b\.c\.|b\.c\.e\.)")
@@ -1291,7 +1436,7 @@ fn parse_frag(string: &mut String, datetime: &mut DateTime) -> bool {
 
         if let Some(n) = caps.get(1)
                 .map(|x| x.as_str())
-                .map(|x| x.parse::<i32>().unwrap()) {
+                .and_then(int_i32) {
             if datetime.hour.is_some() && datetime.mday.is_none() && (1..=31).contains(&n) {
                 datetime.mday = Some(n as u32);
             }
@@ -1307,6 +1452,48 @@ fn parse_frag(string: &mut String, datetime: &mut DateTime) -> bool {
     matched
 }
 
+/// How two digit years are completed to full years.
+///
+/// Every policy maps the two digit years to a window of a hundred
+/// consecutive years, picking the one year in it with the same last two
+/// digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YearPivot {
+    /// The window starts at the given year. Ruby's completion is
+    /// `Fixed(1969)`: 69 is 1969 and 68 is 2068.
+    Fixed(i32),
+    /// The window starts `years_before` years before the `reference` year,
+    /// e.g. `Sliding { reference: 2024, years_before: 80 }` for birthdates
+    /// maps 44 to 1944 and 24 to 2024.
+    Sliding { reference: i32, years_before: u32 },
+    /// The year closest to the `reference` year, ties going to the past.
+    ClosestTo(i32),
+    /// The year closest to the current year.
+    ClosestToNow,
+}
+
+impl Default for YearPivot {
+    fn default() -> YearPivot {
+        YearPivot::Fixed(1969)
+    }
+}
+
+impl YearPivot {
+    /// Completes a year of 0 to 99, leaving other years as they are.
+    pub fn complete(&self, year: i32) -> i32 {
+        if !(0..=99).contains(&year) {
+            return year;
+        }
+        let start = match *self {
+            YearPivot::Fixed(start) => start,
+            YearPivot::Sliding { reference, years_before } => reference.saturating_sub_unsigned(years_before),
+            YearPivot::ClosestTo(reference) => reference.saturating_sub(50),
+            YearPivot::ClosestToNow => chrono::Utc::now().year() - 50,
+        };
+        start.saturating_add((i64::from(year) - i64::from(start)).rem_euclid(100) as i32)
+    }
+}
+
 // Parses the given representation of date and time, and returns a
 // hash of parsed elements. This method does not function as a validator.
 // If the optional second argument is true and the detected year is in the
@@ -1319,110 +1506,160 @@ pub fn date_parse(string: &str, comp: bool) -> DateTime {
 
 // Returns the remaining portion of the string after processing  along with datetime
 pub fn date_parse2(string: &str, comp: bool) -> (DateTime, String) {
-    lazy_static! {
-        static ref NON_DATE_CHARS_RE: Regex = Regex::new(r"[^-+',./:@[:alnum:]\[\]]+").unwrap();
-    }
-
-    let string = string.to_owned();
-
-    let mut string = NON_DATE_CHARS_RE.replace_all(&string, SPACE).to_string();
-    let mut datetime  = DateTime { comp: Some(comp), ..Default::default() };
+    date_parse_internal(string, comp.then(YearPivot::default), None)
+}
 
-    if check_classes(&string, HAVE_ALPHA) {
-        parse_day(&mut string, &mut datetime);
-    }
+// Same as date_parse with comp, but completes the two digit years
+// following +pivot+ instead of Ruby's 69 cutoff.
+//
+// date_parse_with_year_pivot('01-02-03', YearPivot::Fixed(1950)) #=> {:year=>2001, :mon=>2, :mday=>3}
+// date_parse_with_year_pivot('51-02-03', YearPivot::Fixed(1950)) #=> {:year=>1951, :mon=>2, :mday=>3}
+pub fn date_parse_with_year_pivot(string: &str, pivot: YearPivot) -> DateTime {
+    let (dt, _unparsed) = date_parse_internal(string, Some(pivot), None);
+    dt
+}
 
-    if check_classes(&string, HAVE_DIGIT) {
-        parse_time(&mut string, &mut datetime);
-    }
+// Same as date_parse, with the year completion and date order of
+// +options+. A year block of the options turns the completion off.
+//
+// date_parse_with_options('03/04/2024', ParseOptions::new().date_order(DateOrder::Mdy))
+//     #=> {:year=>2024, :mon=>3, :mday=>4}
+pub fn date_parse_with_options(string: &str, options: &ParseOptions) -> DateTime {
+    let now_year = options.now.map(|now| now.year()).unwrap_or_else(|| chrono::Utc::now().year());
+    let (dt, _unparsed) = date_parse_internal(string, options.year_pivot_at(now_year), options.date_order);
+    dt
+}
 
-    let mut matched = false;
-    if check_classes(&string, HAVE_ALPHA | HAVE_DIGIT) {
-        matched =  parse_eu(&mut string, &mut datetime);
+type DateParser = fn(&mut String, &mut DateTime, Option<DateOrder>) -> bool;
+
+// The sub-parsers for the date itself, in the order they are tried until
+// one of them matches, along with the classes of characters the string
+// needs for them to be tried at all.
+pub(super) static DATE_PARSERS: [(&str, u32, DateParser); 12] = [
+    ("parse_eu", HAVE_ALPHA | HAVE_DIGIT, |s, d, _| parse_eu(s, d)),
+    ("parse_us", HAVE_ALPHA | HAVE_DIGIT, |s, d, _| parse_us(s, d)),
+    ("parse_iso", HAVE_DIGIT | HAVE_DASH, |s, d, _| parse_iso(s, d)),
+    ("parse_jis", HAVE_DIGIT | HAVE_DOT, |s, d, _| parse_jis(s, d)),
+    ("parse_vms", HAVE_ALPHA | HAVE_DIGIT | HAVE_DASH, |s, d, _| parse_vms(s, d)),
+    ("parse_sla", HAVE_DIGIT | HAVE_SLASH, parse_sla),
+    ("parse_dot", HAVE_DIGIT | HAVE_DOT, parse_dot),
+    ("parse_iso2", HAVE_DIGIT, |s, d, _| parse_iso2(s, d)),
+    ("parse_year", HAVE_DIGIT, |s, d, _| parse_year(s, d)),
+    ("parse_mon", HAVE_ALPHA, |s, d, _| parse_mon(s, d)),
+    ("parse_mday", HAVE_DIGIT, |s, d, _| parse_mday(s, d)),
+    ("parse_ddd", HAVE_DIGIT, |s, d, _| parse_ddd(s, d)),
+];
+
+// Replaces everything but the characters of dates with spaces.
+pub(super) fn date_chars(string: &str) -> String {
+    date_chars_mapped(string).0
+}
 
-        if !matched {
-            matched = parse_us(&mut string, &mut datetime);
-        }
+// Same as date_chars, along with the offset in +string+ of each byte of
+// the result.
+pub(super) fn date_chars_mapped(string: &str) -> (String, Vec<usize>) {
+    lazy_static! {
+        // Keeps the Japanese era names of JIS X 0301 along with the ASCII
+        // alphanumerics.
+        static ref NON_DATE_CHARS_RE: Regex = Regex::new(r"[^-+',./:@[:alnum:]\[\]明治大正昭和平成令和ＭＴＳＨＲｍｔｓｈｒ]+").unwrap();
+    }
+    let mut result = String::with_capacity(string.len());
+    let mut origin = Vec::with_capacity(string.len());
+    let mut last = 0;
+    for found in NON_DATE_CHARS_RE.find_iter(string) {
+        result.push_str(&string[last..found.start()]);
+        origin.extend(last..found.start());
+        result.push_str(SPACE);
+        origin.push(found.start());
+        last = found.end();
+    }
+    result.push_str(&string[last..]);
+    origin.extend(last..string.len());
+    (result, origin)
+}
 
+// Runs +parse+ as +parser+, recording what it matched when tracing.
+fn traced(trace: &mut Option<Trace>, parser: &'static str, string: &mut String, datetime: &mut DateTime, parse: impl FnOnce(&mut String, &mut DateTime) -> bool) -> bool {
+    let Some(trace) = trace else {
+        return parse(string, datetime);
+    };
+    let (before, prior) = (string.clone(), datetime.clone());
+    let matched = parse(string, datetime);
+    if matched {
+        trace.record(parser, (&before, &prior), (string, datetime));
     }
-    if !matched && check_classes(&string, HAVE_DIGIT | HAVE_DASH) {
-        matched = parse_iso(&mut string, &mut datetime);
-    }
+    matched
+}
 
-    if !matched && check_classes(&string, HAVE_DIGIT | HAVE_DOT) {
-        matched = parse_jis(&mut string, &mut datetime);
-    }
+// Takes everything but the characters of dates out of the string and
+// parses the zone name, the day of the week and the time from it, which
+// come before the date itself.
+pub(super) fn date_parse_head(input: &str, comp: bool, trace: &mut Option<Trace>) -> (DateTime, String) {
+    let mut string = date_chars(input);
+    let mut datetime  = DateTime { comp: Some(comp), ..Default::default() };
 
-    if !matched && check_classes(&string, HAVE_ALPHA | HAVE_DIGIT | HAVE_DASH) {
-        matched = parse_vms(&mut string, &mut datetime);
+    if check_classes(&string, HAVE_ALPHA | HAVE_SLASH) {
+        traced(trace, "parse_iana_zone", &mut string, &mut datetime, |s, d| parse_iana_zone(input, s, d));
     }
 
-    if !matched && check_classes(&string, HAVE_DIGIT | HAVE_SLASH) {
-        matched = parse_sla(&mut string, &mut datetime);
-    }
-    if !matched && check_classes(&string, HAVE_DIGIT | HAVE_DOT) {
-        matched = parse_dot(&mut string, &mut datetime);
-    }
-    if !matched && check_classes(&string, HAVE_DIGIT) {
-        matched = parse_iso2(&mut string, &mut datetime);
-    }
-    if !matched && check_classes(&string, HAVE_DIGIT) {
-        matched = parse_year(&mut string, &mut datetime);
-    }
-    if !matched && check_classes(&string, HAVE_ALPHA) {
-        matched = parse_mon(&mut string, &mut datetime);
-    }
-    if !matched && check_classes(&string, HAVE_DIGIT) {
-        matched = parse_mday(&mut string, &mut datetime);
+    if check_classes(&string, HAVE_ALPHA) {
+        traced(trace, "parse_day", &mut string, &mut datetime, parse_day);
     }
-    if !matched && check_classes(&string, HAVE_DIGIT) {
-        parse_ddd(&mut string, &mut datetime);
+
+    if check_classes(&string, HAVE_DIGIT) {
+        traced(trace, "parse_time", &mut string, &mut datetime, parse_time);
     }
+    (datetime, string)
+}
 
-    if check_classes(&string, HAVE_ALPHA) {
-        parse_bc(&mut string, &mut datetime);
+// Parses the era and the fragments left after the date, then completes the
+// year and works out the offset of the zone.
+pub(super) fn date_parse_tail(string: &mut String, datetime: &mut DateTime, pivot: Option<YearPivot>, trace: &mut Option<Trace>) {
+    if check_classes(string, HAVE_ALPHA) {
+        traced(trace, "parse_bc", string, datetime, parse_bc);
 
     }
-    if check_classes(&string, HAVE_DIGIT) {
-        parse_frag(&mut string, &mut datetime);
+    if check_classes(string, HAVE_DIGIT) {
+        traced(trace, "parse_frag", string, datetime, parse_frag);
     }
 
     if datetime.bc {
         if let Some(cwyear) =  datetime.cwyear {
-            datetime.cwyear = Some(-cwyear + 1);
+            datetime.cwyear = Some(1i32.saturating_sub(cwyear));
         }
         if let Some(year) =  datetime.year {
-            datetime.year = Some(-year + 1);
+            datetime.year = Some(1i32.saturating_sub(year));
         }
     }
 
-    if let Some(true) = datetime.comp.take() {
-        if let Some(cwyear) = datetime.cwyear {
-            if (0..=99).contains(&cwyear) {
-                if cwyear >= 69 {
-                    datetime.cwyear = Some(cwyear + 1900);
-                } else {
-                    datetime.cwyear = Some(cwyear + 2000);
-                }
-            }
-        }
+    if let (Some(true), Some(pivot)) = (datetime.comp.take(), pivot) {
+        datetime.cwyear = datetime.cwyear.map(|cwyear| pivot.complete(cwyear));
+        datetime.year = datetime.year.map(|year| pivot.complete(year));
+    }
 
-        if let Some(year) = datetime.year {
-            if (0..=99).contains(&year) {
-                if year >= 69 {
-                    datetime.year = Some(year + 1900);
-                } else {
-                    datetime.year = Some(year + 2000);
-                }
+    if let Some(zone) = &datetime.zone {
+        if datetime.offset.is_none() {
+            datetime.offset = date_zone_to_diff_at(zone.as_str(), datetime);
+            if let (Some(trace), Some(_)) = (trace, datetime.offset) {
+                trace.derive("offset", "zone");
             }
         }
     }
+}
 
-    if let Some(zone) = &datetime.zone {
-        if datetime.offset.is_none() {
-            datetime.offset = date_zone_to_diff(zone.as_str());
+pub(super) fn date_parse_internal(string: &str, pivot: Option<YearPivot>, order: Option<DateOrder>) -> (DateTime, String) {
+    date_parse_traced(string, pivot, order, &mut None)
+}
+
+pub(super) fn date_parse_traced(string: &str, pivot: Option<YearPivot>, order: Option<DateOrder>, trace: &mut Option<Trace>) -> (DateTime, String) {
+    let (mut datetime, mut string) = date_parse_head(string, pivot.is_some(), trace);
+
+    for (name, classes, parser) in &DATE_PARSERS {
+        if check_classes(&string, *classes) && traced(trace, name, &mut string, &mut datetime, |s, d| parser(s, d, order)) {
+            break;
         }
     }
 
+    date_parse_tail(&mut string, &mut datetime, pivot, trace);
     (datetime, string.trim().to_owned())
 }
@@ -0,0 +1,218 @@
+use chrono::Datelike;
+use regex::{Captures, Regex, RegexBuilder};
+
+use crate::{DateTime, ErrorKind, ParseError};
+use super::interval::Interval;
+use super::parse::{date_parse_internal, DateOrder, YearPivot};
+use super::relative::WEEKDAY;
+use super::time::{has_date, make_week_time, unrepresentable_year, LocalZone};
+use super::ParseOptions;
+
+const SEP: &str = r"(?:\s*[-–—‐]\s*|\s+(?:to|through|thru|until|till)\s+)";
+const MONTH: &str = r"(?:jan(?:uary)?|feb(?:ruary)?|mar(?:ch)?|apr(?:il)?|may|june?|july?|aug(?:ust)?|sep(?:t(?:ember)?)?|oct(?:ober)?|nov(?:ember)?|dec(?:ember)?)\b\.?";
+const DAY: &str = r"\d{1,2}(?:st|nd|rd|th)?\b";
+const TIME: &str = r"\d{1,2}(?::\d{2}){1,2}(?:\s*[ap]\.?m\b\.?)?|\d{1,2}\s*[ap]\.?m\b\.?";
+
+fn range_regex(pattern: &str) -> Regex {
+    let pattern = pattern.replace("{sep}", SEP).replace("{month}", MONTH).replace("{day}", DAY).replace("{time}", TIME).replace("{weekday}", WEEKDAY);
+    RegexBuilder::new(&pattern).case_insensitive(true).build().unwrap()
+}
+
+// The ranges written in short, which share the rest of the text: the
+// first and last value of each go in the text of the start and end.
+//
+//   9am-5pm, 10:00 to 14:30       Jan 3-7, 3-5 March
+//   Mon-Fri, Monday to Friday     2023-2024
+fn short_range(text: &str) -> Option<(std::ops::Range<usize>, String, String)> {
+    lazy_static! {
+        static ref TIMES_RE: Regex = range_regex(r"\b(?P<a>{time}){sep}(?P<b>{time})");
+        static ref US_DAYS_RE: Regex = range_regex(r"\b(?P<mon>{month})\s*(?P<a>{day}){sep}(?P<b>{day})");
+        static ref EU_DAYS_RE: Regex = range_regex(r"\b(?P<a>{day}){sep}(?P<b>{day})\s+(?:of\s+)?(?P<mon>{month})");
+        static ref WEEKDAYS_RE: Regex = range_regex(r"\b(?P<a>(?:{weekday})\b\.?){sep}(?P<b>(?:{weekday})\b\.?)");
+        static ref YEARS_RE: Regex = range_regex(r"\b(?P<a>\d{4}){sep}(?P<b>\d{4})\b");
+    }
+    // Not a part of a time or date, as "00-12" in "10:00-12 March"
+    let apart = |caps: &Captures| {
+        let range = caps.get(0).map_or(0..0, |x| x.range());
+        !text[..range.start].ends_with([':', '-', '/']) && !text[range.end..].starts_with([':', '-', '/'])
+    };
+    let day = |caps: &Captures, x: &str| match caps.name("mon") {
+        Some(mon) if caps.get(0)?.start() == mon.start() => Some(format!("{} {}", mon.as_str(), &caps[x])),
+        Some(mon) => Some(format!("{} {}", &caps[x], mon.as_str())),
+        None => Some(caps[x].to_owned()),
+    };
+    for re in [&*TIMES_RE, &*US_DAYS_RE, &*EU_DAYS_RE, &*WEEKDAYS_RE] {
+        if let Some(caps) = re.captures(text).filter(apart) {
+            return Some((caps.get(0)?.range(), day(&caps, "a")?, day(&caps, "b")?));
+        }
+    }
+    // A year alone is read as a month and day, so it is kept for later.
+    let caps = YEARS_RE.captures(text).filter(apart)?;
+    Some((caps.get(0)?.range(), format!("\u{E000}{}", &caps["a"]), format!("\u{E000}{}", &caps["b"])))
+}
+
+// The texts of the start and end of the range in +string+.
+fn split(string: &str) -> Option<(String, String)> {
+    lazy_static! {
+        static ref SEP_RE: Regex = range_regex(r"\s+(?:[-–—]|to|through|thru|until|till)\s+|\s*[–—]\s*");
+    }
+    // Each range is taken out of the text and left as a hole, a private
+    // use character, which the later ones don't match.
+    let mut holes = string.to_owned();
+    let mut values = Vec::new();
+    while let Some((range, a, b)) = short_range(&holes) {
+        let hole = char::from_u32(0xF000 + values.len() as u32)?;
+        holes.replace_range(range, &hole.to_string());
+        values.push((hole, a, b));
+    }
+    let (mut start, mut end) = (holes.clone(), holes);
+    for (hole, a, b) in &values {
+        start = start.replace(*hole, a);
+        end = end.replace(*hole, b);
+    }
+    // Two dates written in full, "Jan 3 - Feb 5, 2024"
+    if values.is_empty() {
+        let sep = SEP_RE.find(string)?;
+        start.truncate(sep.start());
+        end.replace_range(..sep.end(), "");
+    }
+    Some((start, end))
+}
+
+// Takes the year of a range of years out of +text+.
+fn take_year(text: &mut String) -> Option<i32> {
+    lazy_static! {
+        static ref YEAR_RE: Regex = Regex::new(r"\x{E000}(\d{4})").unwrap();
+    }
+    let year = YEAR_RE.captures(text)?[1].parse::<i32>().ok();
+    *text = YEAR_RE.replace(text, " ").into_owned();
+    year
+}
+
+pub(super) fn range_fields(string: &str, pivot: Option<YearPivot>, order: Option<DateOrder>) -> Option<(DateTime, DateTime)> {
+    let (mut start_text, mut end_text) = split(string)?;
+    let years = (take_year(&mut start_text), take_year(&mut end_text));
+    let (mut start, _) = date_parse_internal(&start_text, pivot, order);
+    let (mut end, _) = date_parse_internal(&end_text, pivot, order);
+    start.year = start.year.or(years.0);
+    end.year = end.year.or(years.1);
+    if !found(&start) || !found(&end) {
+        return None;
+    }
+    propagate(&mut start, &mut end)?;
+    Some((start, end))
+}
+
+fn found(d: &DateTime) -> bool {
+    has_date(d) || d.wday.is_some() || d.hour.is_some() || d.min.is_some()
+}
+
+// Fills in the fields written once for both ends: the date of an end that
+// is only a time, as in "Jan 3 10:00 - 14:00", the year, going by the
+// order of the months and days so that "Dec 28 - Jan 3, 2025" starts in
+// 2024, and the zone. Fails when the year is out of range.
+fn propagate(start: &mut DateTime, end: &mut DateTime) -> Option<()> {
+    if !has_date(end) && end.wday.is_none() {
+        (end.year, end.mon, end.mday, end.yday, end.wday) = (start.year, start.mon, start.mday, start.yday, start.wday);
+        (end.cwyear, end.cweek, end.cwday, end.wnum0, end.wnum1) = (start.cwyear, start.cweek, start.cwday, start.wnum0, start.wnum1);
+    }
+    let (first, last) = ((start.mon, start.mday), (end.mon, end.mday));
+    match (start.year, end.year) {
+        (None, Some(year)) if start.mon.is_some() => start.year = Some(if first > last { year.checked_sub(1)? } else { year }),
+        (Some(year), None) if end.mon.is_some() => end.year = Some(if first > last { year.checked_add(1)? } else { year }),
+        _ => {}
+    }
+    if start.zone.is_none() {
+        (start.zone, start.offset) = (end.zone.clone(), end.offset);
+    }
+    if end.zone.is_none() {
+        (end.zone, end.offset) = (start.zone.clone(), start.offset);
+    }
+    Some(())
+}
+
+/// Parses a range of dates or times written in prose into the fields of
+/// its start and end, each read like `date_parse` does. Fields written
+/// once, like the year of "Jan 3-7, 2024" or the zone of "10:00 to 14:30
+/// CET", are set on both.
+///
+/// date_parse_range('Jan 3–7, 2024', true)
+///     #=> [{:year=>2024, :mon=>1, :mday=>3}, {:year=>2024, :mon=>1, :mday=>7}]
+/// date_parse_range('Mon–Fri 9am–5pm', true)
+///     #=> [{:wday=>1, :hour=>9}, {:wday=>5, :hour=>17}]
+/// date_parse_range('from 10:00 to 14:30 CET', true)
+///     #=> [{:hour=>10, :min=>0, :zone=>"CET", :offset=>3600}, {:hour=>14, :min=>30, :zone=>"CET", :offset=>3600}]
+///
+/// Returns `None` when there is no range.
+pub fn date_parse_range(string: &str, comp: bool) -> Option<(DateTime, DateTime)> {
+    range_fields(string, comp.then(YearPivot::default), None)
+}
+
+/// Parses a range of dates or times written in prose, as
+/// `date_parse_range` does, into the times of its start and end. The start
+/// is completed like `time::parse` does, and the end from the start, so
+/// "Mon-Fri" ends on the Friday after the Monday and "9am-5pm" ends on the
+/// same day. The end is exclusive: an end with a time ends at that time,
+/// and one without runs to the start of the day, week, month or year after
+/// the last one written.
+///
+/// parse_range('Jan 3–7, 2024') #=> 2024-01-03 00:00:00..2024-01-08 00:00:00 (local)
+/// parse_range('Mon–Fri')       #=> Monday 00:00:00..Saturday 00:00:00 (local)
+/// parse_range('9am–5pm')       #=> today 09:00:00..today 17:00:00 (local)
+/// parse_range('2023-2024')     #=> 2023-01-01 00:00:00..2025-01-01 00:00:00 (local)
+pub fn parse_range(date: &str) -> crate::Result<Interval> {
+    parse_range_with_options(date, &ParseOptions::new())
+}
+
+/// Same as `parse_range`, with the knobs of `time::parse_with_options`.
+pub fn parse_range_with_options(date: &str, options: &ParseOptions) -> crate::Result<Interval> {
+    let local = LocalZone::from_options(options);
+    let now = options.now.unwrap_or_else(|| local.now());
+    let (start, end) = range_fields(date, options.year_pivot_at(now.year()), options.date_order)
+        .ok_or_else(|| ParseError::new(ErrorKind::NoDateFound).with_input(date))?;
+    let time = |d: &DateTime, now| {
+        let year = match (d.year, &options.year_fn) {
+            (Some(y), Some(func)) => Some(func(y)),
+            (year, _) => year,
+        };
+        // A year alone, as in "2023-2024", starts on January 1st.
+        let mut d = d.clone();
+        if d.year.is_some() && !found(&DateTime { year: None, ..d.clone() }) {
+            d.mon = Some(1);
+        }
+        make_week_time(date, &d, year, now, &local)
+    };
+    let start = time(&start, now)?;
+    let last = time(&end, start)?;
+    let end = if end.hour.is_some() || end.min.is_some() || end.sec.is_some() {
+        last
+    } else {
+        let next = period_after(&end, last).ok_or_else(|| unrepresentable_year(last.year()).with_input(date))?;
+        make_week_time(date, &next, next.year, start, &local)?
+    };
+    Ok(Interval { start, end, duration: None })
+}
+
+// The fields of the start of the day, week, month or year after +last+,
+// the time of the end +d+ of a range without a time, going by the
+// smallest of them written in +d+. None when it is out of range.
+fn period_after(d: &DateTime, last: chrono::DateTime<chrono::FixedOffset>) -> Option<DateTime> {
+    let date = last.date_naive();
+    let next = if d.mday.is_some() || d.wday.is_some() || d.yday.is_some() || d.cwday.is_some() {
+        date.succ_opt()?
+    } else if d.cweek.is_some() || d.wnum0.is_some() || d.wnum1.is_some() {
+        date.checked_add_days(chrono::Days::new(7))?
+    } else if d.mon.is_some() {
+        date.checked_add_months(chrono::Months::new(1))?
+    } else {
+        date.with_year(date.year().checked_add(1)?)?
+    };
+    Some(DateTime {
+        year: Some(next.year()),
+        mon: Some(next.month()),
+        mday: Some(next.day()),
+        zone: d.zone.clone(),
+        offset: d.offset,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,311 @@
+use std::ops::Range;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Months, NaiveDate, Weekday};
+use regex::{Captures, Regex, RegexBuilder};
+
+use crate::{ErrorKind, ParseError};
+use super::duration::parse_duration;
+use super::time::month_days;
+
+// A date or time given relative to now, as read by parse_relative.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Relative {
+    // An instant, as in "now", "3 days ago" and "in 2 hours".
+    Instant(DateTime<FixedOffset>),
+    // A day, as in "tomorrow" and "next Friday", which starts at midnight
+    // unless a time is given next to it.
+    Day(NaiveDate),
+}
+
+impl Relative {
+    pub(super) fn date(&self) -> NaiveDate {
+        match self {
+            Relative::Instant(t) => t.date_naive(),
+            Relative::Day(date) => *date,
+        }
+    }
+}
+
+const UNIT: &str = r"(sec(?:ond)?|min(?:ute)?|hour|day|week|fortnight|month|year)s?";
+const AMOUNT: &str = r"(\d+|an?)";
+pub(super) const WEEKDAY: &str = r"sun(?:day)?|mon(?:day)?|tue(?:s(?:day)?)?|wed(?:nesday)?|thu(?:r(?:s(?:day)?)?)?|fri(?:day)?|sat(?:urday)?";
+const PERIOD: &str = r"(?:
+    (?:(?P<which>the|this|next|last)\s+)?(?P<unit>week|month|quarter|year)
+  | (?P<mon>jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.?(?:\s+(?P<mon_year>\d{4}))?
+  | q(?P<quarter>[1-4])(?:\s+(?P<quarter_year>\d{4}))?
+  | (?P<year>\d{4})
+)";
+
+fn relative_regex(pattern: &str) -> Regex {
+    let pattern = pattern.replace("{amount}", AMOUNT).replace("{unit}", UNIT).replace("{weekday}", WEEKDAY).replace("{period}", PERIOD);
+    RegexBuilder::new(&pattern).case_insensitive(true).ignore_whitespace(true).build().unwrap()
+}
+
+// Reads the first relative expression in +string+ against +now+, and
+// replaces it with a space, leaving the time and zone next to it, as in
+// "tomorrow at 10:00 UTC", for date_parse.
+//
+//   today, tomorrow, yesterday, now
+//   3 days ago, an hour ago, 2 weeks from now, in 10 minutes
+//   next Friday, last Mon, this Sunday
+//   next week, last month, this year, this weekend
+//   second Tuesday of March 2025, last Friday of the month
+//   first business day of Q3, last day of next month
+//   end of month, start of the quarter, end of 2025
+//   now + 1h30m, now - P1D, in 1 day 02:00:00, PT90M ago
+//
+// A named day is the first one after today for "next", the last one before
+// today for "last", and the one in this week, Sunday to Saturday, for
+// "this". Weeks, months and years are taken from their first day and a
+// weekend from its Saturday. Business days are Monday to Friday.
+//
+// The day is an error when the period has no such day, as with the fifth
+// Monday of February 2025.
+pub(super) fn parse_relative(string: &str, now: &DateTime<FixedOffset>) -> Option<(crate::Result<Relative>, String)> {
+    // "2 weeks from now" before "now", and "last Friday of the month"
+    // before "last Friday"
+    let (relative, range) = relative_duration(string, now)
+        .or_else(|| relative_offset(string, now))
+        .or_else(|| relative_in_period(string, now))
+        .or_else(|| relative_day(string, now))
+        .or_else(|| relative_named(string, now))?;
+    let mut rest = string.to_owned();
+    rest.replace_range(range, " ");
+    Some((relative, rest))
+}
+
+fn relative_day(string: &str, now: &DateTime<FixedOffset>) -> Option<(crate::Result<Relative>, Range<usize>)> {
+    lazy_static! {
+        static ref DAY_RE: Regex = relative_regex(r"\b(today|tomorrow|yesterday|now)\b");
+    }
+    let caps = DAY_RE.captures(string)?;
+    let today = now.date_naive();
+    let relative = match caps[1].to_lowercase().as_str() {
+        "today" => Relative::Day(today),
+        "tomorrow" => Relative::Day(today.succ_opt()?),
+        "yesterday" => Relative::Day(today.pred_opt()?),
+        _ => Relative::Instant(*now),
+    };
+    Some((Ok(relative), caps.get(0)?.range()))
+}
+
+// "now + 1h30m", "in P1D" and "1 day 02:00:00 ago", with the whole input
+// being the expression and the duration one that parse_duration reads.
+fn relative_duration(string: &str, now: &DateTime<FixedOffset>) -> Option<(crate::Result<Relative>, Range<usize>)> {
+    lazy_static! {
+        static ref NOW_RE: Regex = relative_regex(r"\A\s*now\s*([-+])\s*(.+?)\s*\z");
+        static ref IN_RE: Regex = relative_regex(r"\A\s*in\s+(.+?)\s*\z");
+        static ref AGO_RE: Regex = relative_regex(r"\A\s*(.+?)\s+ago\s*\z");
+    }
+    let since = |duration: crate::CalendarDuration| {
+        duration.since(*now)
+            .map(Relative::Instant)
+            .ok_or_else(|| ParseError::out_of_range(ErrorKind::UnrepresentableYear, "year", duration, None))
+    };
+    let range = 0..string.len();
+    if let Some(caps) = NOW_RE.captures(string) {
+        let duration = parse_duration(&caps[2]).map(|x| if &caps[1] == "-" { x.negated() } else { Some(x) });
+        let relative = match duration {
+            Ok(Some(duration)) => since(duration),
+            Ok(None) => Err(ParseError::new(ErrorKind::InvalidDuration)),
+            Err(err) => Err(err),
+        };
+        return Some((relative, range));
+    }
+    let duration = match (IN_RE.captures(string), AGO_RE.captures(string)) {
+        (Some(caps), _) => parse_duration(&caps[1]).ok()?,
+        (None, Some(caps)) => parse_duration(&caps[1]).ok()?.negated()?,
+        (None, None) => return None,
+    };
+    Some((since(duration), range))
+}
+
+// "3 days ago", "in 2 hours" and "2 weeks from now".
+fn relative_offset(string: &str, now: &DateTime<FixedOffset>) -> Option<(crate::Result<Relative>, Range<usize>)> {
+    lazy_static! {
+        static ref AGO_RE: Regex = relative_regex(r"\b{amount}\s+{unit}\s+(ago|from\s+now|later|hence)\b");
+        static ref IN_RE: Regex = relative_regex(r"\bin\s+{amount}\s+{unit}\b");
+    }
+    let (caps, sign) = match AGO_RE.captures(string) {
+        Some(caps) => {
+            let sign = if caps[3].eq_ignore_ascii_case("ago") { -1 } else { 1 };
+            (caps, sign)
+        }
+        None => (IN_RE.captures(string)?, 1),
+    };
+    let amount = match caps[1].to_lowercase().as_str() {
+        "a" | "an" => 1,
+        digits => digits.parse::<i64>().ok()?,
+    };
+    let t = shifted(now, amount.checked_mul(sign)?, &caps[2])?;
+    Some((Ok(Relative::Instant(t)), caps.get(0)?.range()))
+}
+
+// +now+ moved by +amount+ of +unit+. Months and years end on the last day
+// of the month when it is shorter, as Date#>> does.
+fn shifted(now: &DateTime<FixedOffset>, amount: i64, unit: &str) -> Option<DateTime<FixedOffset>> {
+    let unit = unit.to_lowercase();
+    let duration = match unit.trim_end_matches('s') {
+        "sec" | "second" => Duration::try_seconds(amount)?,
+        "min" | "minute" => Duration::try_minutes(amount)?,
+        "hour" => Duration::try_hours(amount)?,
+        "day" => Duration::try_days(amount)?,
+        "week" => Duration::try_weeks(amount)?,
+        "fortnight" => Duration::try_weeks(amount.checked_mul(2)?)?,
+        "month" => return add_months(now, amount),
+        _ => return add_months(now, amount.checked_mul(12)?),
+    };
+    now.checked_add_signed(duration)
+}
+
+fn add_months(now: &DateTime<FixedOffset>, months: i64) -> Option<DateTime<FixedOffset>> {
+    let n = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months < 0 { now.checked_sub_months(n) } else { now.checked_add_months(n) }
+}
+
+// "next Friday", "last month" and "this weekend".
+fn relative_named(string: &str, now: &DateTime<FixedOffset>) -> Option<(crate::Result<Relative>, Range<usize>)> {
+    lazy_static! {
+        static ref NAMED_RE: Regex = relative_regex(r"\b(next|last|this)\s+(weekend|week|month|year|{weekday})\b");
+    }
+    let caps = NAMED_RE.captures(string)?;
+    let date = named_day(&caps, now.date_naive())?;
+    Some((Ok(Relative::Day(date)), caps.get(0)?.range()))
+}
+
+fn named_day(caps: &Captures, today: NaiveDate) -> Option<NaiveDate> {
+    let which = caps[1].to_lowercase();
+    let step: i64 = match which.as_str() {
+        "next" => 1,
+        "last" => -1,
+        _ => 0,
+    };
+    let wday = today.weekday().num_days_from_sunday() as i64;
+    let days = |n: i64| today.checked_add_signed(Duration::try_days(n)?);
+    match caps[2].to_lowercase().as_str() {
+        "week" => days(7 * step - wday),
+        "weekend" => {
+            // The weekend under way on Saturday and Sunday, or else the
+            // coming one.
+            let saturday = if wday == 0 { -1 } else { 6 - wday };
+            days(7 * step + saturday)
+        }
+        "month" => {
+            let first = today.with_day(1)?;
+            let n = Months::new(1);
+            match step {
+                1 => first.checked_add_months(n),
+                -1 => first.checked_sub_months(n),
+                _ => Some(first),
+            }
+        }
+        "year" => NaiveDate::from_ymd_opt(today.year().checked_add(step as i32)?, 1, 1),
+        name => {
+            let target = weekday_number(name)?;
+            let ahead = (target - wday).rem_euclid(7);
+            match step {
+                1 => days(if ahead == 0 { 7 } else { ahead }),
+                -1 => days(if ahead == 0 { -7 } else { ahead - 7 }),
+                _ => days(target - wday),
+            }
+        }
+    }
+}
+
+// The day of the week of a name, Sunday being 0.
+fn weekday_number(name: &str) -> Option<i64> {
+    ["sun", "mon", "tue", "wed", "thu", "fri", "sat"].iter()
+        .position(|x| name.starts_with(x))
+        .map(|x| x as i64)
+}
+
+// "second Tuesday of March 2025", "first business day of Q3" and "end of
+// month".
+fn relative_in_period(string: &str, now: &DateTime<FixedOffset>) -> Option<(crate::Result<Relative>, Range<usize>)> {
+    lazy_static! {
+        static ref NTH_RE: Regex = relative_regex(r"
+            \b(?P<nth>first|second|third|fourth|fifth|last|1st|2nd|3rd|4th|5th)\s+
+            (?P<day>(?:business|working)\s+day|weekday|day|{weekday})\s+
+            (?:of|in)\s+{period}\b");
+        static ref EDGE_RE: Regex = relative_regex(r"\b(?P<edge>end|start|beginning)\s+of\s+(?:the\s+)?{period}\b");
+    }
+    let today = now.date_naive();
+    if let Some(caps) = EDGE_RE.captures(string) {
+        let (start, end) = period(&caps, today)?;
+        let date = if caps["edge"].eq_ignore_ascii_case("end") { end } else { start };
+        return Some((Ok(Relative::Day(date)), caps.get(0)?.range()));
+    }
+
+    let caps = NTH_RE.captures(string)?;
+    let range = caps.get(0)?.range();
+    let (start, end) = period(&caps, today)?;
+    let day = caps["day"].to_lowercase();
+    let matches = |date: &NaiveDate| match day.as_str() {
+        "day" => true,
+        "weekday" => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+        day if day.ends_with(" day") => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+        name => weekday_number(name) == Some(date.weekday().num_days_from_sunday() as i64),
+    };
+    let days: Vec<NaiveDate> = start.iter_days().take_while(|x| *x <= end).filter(matches).collect();
+    let nth = caps["nth"].to_lowercase();
+    let n = match nth.as_str() {
+        "last" => days.len().max(1),
+        nth => 1 + ["first", "second", "third", "fourth", "fifth"].iter()
+            .position(|x| *x == nth)
+            .unwrap_or_else(|| nth.as_bytes()[0].saturating_sub(b'1') as usize),
+    };
+    let relative = match days.get(n - 1) {
+        Some(date) => Ok(Relative::Day(*date)),
+        None => Err(ParseError::out_of_range(ErrorKind::FieldOutOfRange, "mday", n, Some(1..=days.len() as i64))
+            .with_span(Some(range.clone()))),
+    };
+    Some((relative, range))
+}
+
+// The first and last day of the period captured by PERIOD, the current one
+// for "the month" or a month without a year.
+fn period(caps: &Captures, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let year_of = |name: &str| match caps.name(name) {
+        Some(year) => year.as_str().parse::<i32>().ok(),
+        None => Some(today.year()),
+    };
+    let months = |first: NaiveDate, count: u32| {
+        let last = first.checked_add_months(Months::new(count - 1))?;
+        Some((first, last.with_day(month_days(last.year(), last.month() as i32).ok()? as u32)?))
+    };
+    if let Some(mon) = caps.name("mon") {
+        let mon = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"].iter()
+            .position(|x| mon.as_str().eq_ignore_ascii_case(x))? as u32 + 1;
+        return months(NaiveDate::from_ymd_opt(year_of("mon_year")?, mon, 1)?, 1);
+    }
+    if let Some(quarter) = caps.name("quarter") {
+        let quarter = quarter.as_str().parse::<u32>().ok()?;
+        return months(NaiveDate::from_ymd_opt(year_of("quarter_year")?, 3 * quarter - 2, 1)?, 3);
+    }
+    if caps.name("year").is_some() {
+        return months(NaiveDate::from_ymd_opt(year_of("year")?, 1, 1)?, 12);
+    }
+
+    let step: i32 = match caps.name("which").map(|x| x.as_str().to_lowercase()).as_deref() {
+        Some("next") => 1,
+        Some("last") => -1,
+        _ => 0,
+    };
+    let shift = |first: NaiveDate, months: i32| {
+        let n = Months::new(months.unsigned_abs());
+        if months < 0 { first.checked_sub_months(n) } else { first.checked_add_months(n) }
+    };
+    match caps["unit"].to_lowercase().as_str() {
+        "week" => {
+            let wday = today.weekday().num_days_from_sunday() as i64;
+            let start = today.checked_add_signed(Duration::try_days(7 * step as i64 - wday)?)?;
+            Some((start, start.checked_add_signed(Duration::try_days(6)?)?))
+        }
+        "month" => months(shift(today.with_day(1)?, step)?, 1),
+        "quarter" => {
+            let first = NaiveDate::from_ymd_opt(today.year(), today.month0() / 3 * 3 + 1, 1)?;
+            months(shift(first, 3 * step)?, 3)
+        }
+        _ => months(NaiveDate::from_ymd_opt(today.year().checked_add(step)?, 1, 1)?, 12),
+    }
+}
@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::DateTime;
+use super::parse::{date_chars_mapped, date_parse_traced, YearPivot};
+
+/// Where a field of `date_parse_with_spans` came from: the byte range in
+/// the input of the text the sub-parser matched, and the name of the
+/// sub-parser, such as `parse_eu` or `parse_time`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FieldSpan {
+    pub field: &'static str,
+    pub start: usize,
+    pub end: usize,
+    pub parser: &'static str,
+}
+
+impl FieldSpan {
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+// Names of the fields that differ between +a+ and +b+.
+fn changed_fields(a: &DateTime, b: &DateTime) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    macro_rules! compare {
+        ($($field:ident),*) => {
+            $(if a.$field != b.$field { fields.push(stringify!($field)); })*
+        };
+    }
+    compare!(year, mon, mday, yday, wday, cwyear, cweek, cwday, wnum0, wnum1, hour, min, sec, sec_fraction, seconds, offset, zone, bc);
+    fields
+}
+
+// Keeps track of what the sub-parsers of date_parse match. The string they
+// work on is rewritten, the matches replaced with a space, so +origin+
+// holds the offset in the input of each byte of the string.
+pub(super) struct Trace {
+    input: String,
+    origin: Vec<usize>,
+    pub(super) spans: Vec<FieldSpan>,
+}
+
+impl Trace {
+    pub(super) fn new(input: &str) -> Trace {
+        let (_string, origin) = date_chars_mapped(input);
+        Trace { input: input.to_owned(), origin, spans: Vec::new() }
+    }
+
+    // Records the fields +parser+ set on its way from +before+ to +after+.
+    // Sub-parsers replace one match, so the part of +before+ that isn't
+    // common with +after+ at either end is what they matched.
+    pub(super) fn record(&mut self, parser: &'static str, before: (&str, &DateTime), after: (&str, &DateTime)) {
+        let ((old, prior), (new, datetime)) = (before, after);
+        let (old_bytes, new_bytes) = (old.as_bytes(), new.as_bytes());
+        let mut prefix = old_bytes.iter().zip(new_bytes).take_while(|(a, b)| a == b).count();
+        while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+        let limit = old.len().min(new.len()) - prefix;
+        let mut suffix = old_bytes.iter().rev().zip(new_bytes.iter().rev()).take(limit).take_while(|(a, b)| a == b).count();
+        while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+            suffix -= 1;
+        }
+        let (end, new_end) = (old.len() - suffix, new.len() - suffix);
+
+        let fields = changed_fields(prior, datetime);
+        if end > prefix && !fields.is_empty() {
+            let start = self.origin[prefix];
+            let mut stop = self.origin[end - 1] + 1;
+            while !self.input.is_char_boundary(stop) {
+                stop += 1;
+            }
+            let text = &self.input[start..stop];
+            let start = start + (text.len() - text.trim_start().len());
+            let stop = (stop - (text.len() - text.trim_end().len())).max(start);
+            for field in fields {
+                self.set(FieldSpan { field, start, end: stop, parser });
+            }
+        }
+
+        let fill = self.origin.get(prefix).copied().unwrap_or(self.input.len());
+        self.origin.splice(prefix..end, std::iter::repeat_n(fill, new_end - prefix));
+    }
+
+    // Gives +field+ the span of +from+, for fields worked out from others.
+    pub(super) fn derive(&mut self, field: &'static str, from: &'static str) {
+        if let Some(span) = self.spans.iter().find(|x| x.field == from).cloned() {
+            self.set(FieldSpan { field, ..span });
+        }
+    }
+
+    fn set(&mut self, span: FieldSpan) {
+        self.spans.retain(|x| x.field != span.field);
+        self.spans.push(span);
+    }
+}
+
+/// Same as `date_parse`, along with where each field came from in the
+/// input, in the order the sub-parsers set them. A field set twice has
+/// the span of the last sub-parser; the offset of a zone name has the span
+/// of the name.
+///
+/// date_parse_with_spans('Sat Aug 28 02:55:50 2021', false)
+///     #=> [{:field=>:wday, :range=>0...3, :parser=>"parse_day"},
+///          {:field=>:hour, :range=>11...19, :parser=>"parse_time"}, ...,
+///          {:field=>:year, :range=>4...24, :parser=>"parse_us"}, ...]
+pub fn date_parse_with_spans(string: &str, comp: bool) -> (DateTime, Vec<FieldSpan>) {
+    let mut trace = Some(Trace::new(string));
+    let (datetime, _unparsed) = date_parse_traced(string, comp.then(YearPivot::default), None, &mut trace);
+    (datetime, trace.map(|x| x.spans).unwrap_or_default())
+}
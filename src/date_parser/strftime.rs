@@ -0,0 +1,405 @@
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Offset, TimeZone, Timelike};
+
+use super::parse::ERAS;
+
+// Widths beyond this are almost certainly a mistake and would only
+// allocate huge padding strings, so such directives are copied verbatim.
+const MAX_WIDTH: usize = 1024;
+
+const DAY_NAMES: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+#[derive(Default)]
+struct Flags {
+    // None means the directive's own padding, Some('\0') means no padding ('-').
+    padding: Option<char>,
+    upper: bool,
+    chcase: bool,
+    width: Option<usize>,
+    colons: usize,
+}
+
+impl Flags {
+    fn pad_char(&self, default: char) -> Option<char> {
+        match self.padding {
+            Some('\0') => None,
+            Some(c) => Some(c),
+            None => Some(default),
+        }
+    }
+}
+
+fn format_number(n: i64, flags: &Flags, default_pad: char, default_width: usize) -> String {
+    let width = flags.width.unwrap_or(default_width);
+    let digits = n.unsigned_abs().to_string();
+    let sign = if n < 0 { "-" } else { "" };
+    match flags.pad_char(default_pad) {
+        None => format!("{}{}", sign, digits),
+        Some('0') => {
+            let len = width.saturating_sub(sign.len());
+            format!("{}{:0>len$}", sign, digits, len = len)
+        },
+        Some(c) => {
+            let value = format!("{}{}", sign, digits);
+            pad_left(&value, c, width)
+        },
+    }
+}
+
+fn pad_left(value: &str, c: char, width: usize) -> String {
+    let len = value.chars().count();
+    if len >= width {
+        return value.to_owned();
+    }
+    let mut result = c.to_string().repeat(width - len);
+    result.push_str(value);
+    result
+}
+
+fn format_string(value: &str, flags: &Flags) -> String {
+    let value = if flags.upper || flags.chcase {
+        value.to_uppercase()
+    } else {
+        value.to_owned()
+    };
+    match (flags.width, flags.pad_char(' ')) {
+        (Some(width), Some(c)) => pad_left(&value, c, width),
+        _ => value,
+    }
+}
+
+// Years are written with at least four digits, plus the sign when negative.
+fn format_year(year: i64, flags: &Flags) -> String {
+    format_number(year, flags, '0', if year < 0 { 5 } else { 4 })
+}
+
+fn format_fraction(nanos: u32, flags: &Flags, default_width: usize) -> String {
+    let width = flags.width.unwrap_or(default_width);
+    let mut digits = format!("{:09}", nanos);
+    if width <= 9 {
+        digits.truncate(width);
+    } else {
+        digits.push_str(&"0".repeat(width - 9));
+    }
+    digits
+}
+
+fn format_offset(off: i32, flags: &Flags) -> String {
+    let sign = if off < 0 { '-' } else { '+' };
+    let off = off.unsigned_abs();
+    let (hours, mins, secs) = (off / 3600, off / 60 % 60, off % 60);
+    let precision = flags.width.unwrap_or_default();
+    let (hour_width, tail) = match flags.colons {
+        0 => (if precision <= 5 { 2 } else { precision - 3 }, format!("{:02}", mins)),
+        1 => (if precision <= 6 { 2 } else { precision - 4 }, format!(":{:02}", mins)),
+        2 => (if precision <= 9 { 2 } else { precision - 7 }, format!(":{:02}:{:02}", mins, secs)),
+        _ => {
+            if off.is_multiple_of(3600) {
+                (if precision <= 3 { 2 } else { precision - 1 }, String::new())
+            } else if off.is_multiple_of(60) {
+                (if precision <= 6 { 2 } else { precision - 4 }, format!(":{:02}", mins))
+            } else {
+                (if precision <= 9 { 2 } else { precision - 7 }, format!(":{:02}:{:02}", mins, secs))
+            }
+        },
+    };
+    let head = match flags.pad_char('0') {
+        None => format!("{}{}", sign, hours),
+        Some('0') => format!("{}{:0>w$}", sign, hours, w = hour_width),
+        Some(c) => pad_left(&format!("{}{}", sign, hours), c, hour_width + 1),
+    };
+    format!("{}{}", head, tail)
+}
+
+// A FixedOffset carries no abbreviation, so the name is recovered the way
+// Ruby would report it: UTC for a zero offset, otherwise the system zone's
+// abbreviation when it has the same offset at that instant.
+fn zone_name(dt: &DateTime<FixedOffset>) -> String {
+    let off = dt.offset().local_minus_utc();
+    if off == 0 {
+        return "UTC".to_owned();
+    }
+    if let Ok(tz_str) = iana_time_zone::get_timezone() {
+        if let Ok(tz) = tz_str.parse::<chrono_tz::Tz>() {
+            let local = tz.from_utc_datetime(&dt.naive_utc());
+            if local.offset().fix().local_minus_utc() == off {
+                return local.offset().to_string();
+            }
+        }
+    }
+    String::new()
+}
+
+fn week_number(dt: &DateTime<FixedOffset>, first_day: u32) -> i64 {
+    let yday = dt.ordinal0() as i64;
+    let wday = ((dt.weekday().num_days_from_sunday() + 7 - first_day) % 7) as i64;
+    (yday + 7 - wday) / 7
+}
+
+fn strftime_internal(dt: &DateTime<FixedOffset>, format: &str, out: &mut String) {
+    let mut chars = format.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+
+        let mut flags = Flags::default();
+        let mut end = start + 1;
+        let mut directive = None;
+        while let Some(&(i, c)) = chars.peek() {
+            chars.next();
+            end = i + c.len_utf8();
+            match c {
+                '-' => flags.padding = Some('\0'),
+                '_' => flags.padding = Some(' '),
+                '0' if flags.width.is_none() => flags.padding = Some('0'),
+                '^' => flags.upper = true,
+                '#' => flags.chcase = true,
+                ':' => flags.colons += 1,
+                '1'..='9' | '0' => {
+                    let digit = c.to_digit(10).unwrap_or_default() as usize;
+                    flags.width = Some(flags.width.unwrap_or_default().saturating_mul(10).saturating_add(digit));
+                },
+                // Locale modifiers have no effect outside of the C locale.
+                'E' if matches!(chars.peek(), Some((_, 'c' | 'C' | 'x' | 'X' | 'y' | 'Y'))) => {},
+                'O' if matches!(chars.peek(), Some((_, 'd' | 'e' | 'H' | 'k' | 'I' | 'l' | 'm' | 'M' | 'S' | 'u' | 'U' | 'V' | 'w' | 'W' | 'y'))) => {},
+                _ => {
+                    directive = Some(c);
+                    break;
+                },
+            }
+        }
+
+        if (flags.colons > 0 && directive != Some('z')) || flags.width.unwrap_or_default() > MAX_WIDTH {
+            directive = None;
+        }
+
+        let formatted = match directive {
+            Some('Y') => format_year(dt.year() as i64, &flags),
+            Some('C') => format_number((dt.year() as i64).div_euclid(100), &flags, '0', 2),
+            Some('y') => format_number((dt.year() as i64).rem_euclid(100), &flags, '0', 2),
+            Some('m') => format_number(dt.month() as i64, &flags, '0', 2),
+            Some('B') => format_string(MONTH_NAMES[dt.month0() as usize], &flags),
+            Some('b') | Some('h') => format_string(&MONTH_NAMES[dt.month0() as usize][..3], &flags),
+            Some('d') => format_number(dt.day() as i64, &flags, '0', 2),
+            Some('e') => format_number(dt.day() as i64, &flags, ' ', 2),
+            Some('j') => format_number(dt.ordinal() as i64, &flags, '0', 3),
+            Some('H') => format_number(dt.hour() as i64, &flags, '0', 2),
+            Some('k') => format_number(dt.hour() as i64, &flags, ' ', 2),
+            Some('I') => format_number(dt.hour12().1 as i64, &flags, '0', 2),
+            Some('l') => format_number(dt.hour12().1 as i64, &flags, ' ', 2),
+            Some(c @ ('P' | 'p')) => {
+                let pm = if dt.hour12().0 { "PM" } else { "AM" };
+                let lower = (c == 'p' && flags.chcase) || (c == 'P' && !flags.chcase && !flags.upper);
+                let flags = Flags { upper: false, chcase: false, ..flags };
+                format_string(&if lower { pm.to_lowercase() } else { pm.to_owned() }, &flags)
+            },
+            Some('M') => format_number(dt.minute() as i64, &flags, '0', 2),
+            Some('S') => format_number(dt.second() as i64, &flags, '0', 2),
+            Some('L') => format_fraction(dt.nanosecond(), &flags, 3),
+            Some('N') => format_fraction(dt.nanosecond(), &flags, 9),
+            Some('z') => format_offset(dt.offset().local_minus_utc(), &flags),
+            Some('Z') => {
+                let name = zone_name(dt);
+                if flags.chcase {
+                    format_string(&name.to_lowercase(), &Flags { chcase: false, ..flags })
+                } else {
+                    format_string(&name, &flags)
+                }
+            },
+            Some('A') => format_string(DAY_NAMES[dt.weekday().num_days_from_sunday() as usize], &flags),
+            Some('a') => format_string(&DAY_NAMES[dt.weekday().num_days_from_sunday() as usize][..3], &flags),
+            Some('u') => format_number(dt.weekday().number_from_monday() as i64, &flags, '0', 1),
+            Some('w') => format_number(dt.weekday().num_days_from_sunday() as i64, &flags, '0', 1),
+            Some('U') => format_number(week_number(dt, 0), &flags, '0', 2),
+            Some('W') => format_number(week_number(dt, 1), &flags, '0', 2),
+            Some('G') => format_year(dt.iso_week().year() as i64, &flags),
+            Some('g') => format_number((dt.iso_week().year() as i64).rem_euclid(100), &flags, '0', 2),
+            Some('V') => format_number(dt.iso_week().week() as i64, &flags, '0', 2),
+            Some('s') => format_number(dt.timestamp(), &flags, '0', 1),
+            Some('n') => "\n".to_owned(),
+            Some('t') => "\t".to_owned(),
+            Some('%') => "%".to_owned(),
+            Some(c @ ('c' | 'D' | 'x' | 'F' | 'T' | 'X' | 'R' | 'r' | 'v' | '+')) => {
+                let sub_format = match c {
+                    'c' => "%a %b %e %H:%M:%S %Y",
+                    'D' | 'x' => "%m/%d/%y",
+                    'F' => "%Y-%m-%d",
+                    'T' | 'X' => "%H:%M:%S",
+                    'R' => "%H:%M",
+                    'r' => "%I:%M:%S %p",
+                    'v' => "%e-%^b-%4Y",
+                    _ => "%a %b %e %H:%M:%S %Z %Y",
+                };
+                let mut sub = String::new();
+                strftime_internal(dt, sub_format, &mut sub);
+                format_string(&sub, &flags)
+            },
+            // Unknown directives are copied through verbatim.
+            _ => format[start..end].to_owned(),
+        };
+        out.push_str(&formatted);
+    }
+}
+
+/// Formats +time+ according to the directives in the given format string,
+/// following Ruby's <code>Time#strftime</code>.
+///
+/// The directives begin with a percent (%) character. Any text not listed
+/// as a directive will be passed through to the output string.
+///
+/// The directive consists of a percent (%) character, zero or more flags,
+/// optional minimum field width, optional modifier and a conversion
+/// specifier as follows:
+///
+/// ```text
+/// %<flags><width><modifier><conversion>
+/// ```
+///
+/// Flags:
+///
+/// ```text
+/// -  don't pad a numerical output
+/// _  use spaces for padding
+/// 0  use zeros for padding
+/// ^  upcase the result string
+/// #  change case
+/// :  use colons for %z
+/// ```
+///
+/// The minimum field width specifies the minimum width. For %L and %N it
+/// is the number of fractional digits instead (%3N is milliseconds, %9N
+/// nanoseconds).
+///
+/// ```ruby
+/// t = Time.new(2007,11,19,8,37,48,"-06:00") #=> 2007-11-19 08:37:48 -0600
+/// t.strftime("Printed on %m/%d/%Y")         #=> "Printed on 11/19/2007"
+/// t.strftime("at %I:%M %p")                 #=> "at 08:37 AM"
+/// ```
+pub fn strftime(time: &DateTime<FixedOffset>, format: &str) -> String {
+    let mut out = String::with_capacity(format.len() * 2);
+    strftime_internal(time, format, &mut out);
+    out
+}
+
+/// Returns a string in a JIS X 0301 format, the era initial followed by the
+/// two digit era year, month and day (`R06.10.18`). Dates before
+/// 1873-01-01, when Japan adopted the Gregorian calendar, are formatted as
+/// ISO 8601 (`1872-12-31`).
+///
+/// ```ruby
+/// Date.new(2001,2,3).jisx0301    #=> "H13.02.03"
+/// ```
+pub fn to_jisx0301(date: &NaiveDate) -> String {
+    let era = ERAS.iter().rev().find(|era| {
+        let (y, m, d) = era.start;
+        NaiveDate::from_ymd_opt(y, m, d).is_some_and(|start| *date >= start)
+    });
+    match era {
+        Some(era) => format!("{}{:02}.{:02}.{:02}", era.initial, date.year() - era.base, date.month(), date.day()),
+        None => format!("{:04}-{:02}-{:02}", date.year(), date.month(), date.day()),
+    }
+}
+
+/// Returns a string in a JIS X 0301 format with the time and offset, `n`
+/// being the number of fractional digits of the seconds.
+///
+/// ```ruby
+/// DateTime.parse('2001-02-03T04:05:06.123456789+07:00').jisx0301(3)
+///                                 #=> "H13.02.03T04:05:06.123+07:00"
+/// ```
+pub fn to_jisx0301_time(time: &DateTime<FixedOffset>, n: usize) -> String {
+    let format = if n > 0 { format!("T%H:%M:%S.%{}N%:z", n) } else { "T%H:%M:%S%:z".to_owned() };
+    to_jisx0301(&time.date_naive()) + &strftime(time, &format)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{strftime, to_jisx0301, to_jisx0301_time};
+    use chrono::Timelike;
+    use crate::{local_time, utc_time};
+
+    #[test]
+    fn test_strftime() {
+        let t = local_time!(2007,11,19,8,37,48,"-06:00");
+        assert_eq!(strftime(&t, "Printed on %m/%d/%Y"), "Printed on 11/19/2007");
+        assert_eq!(strftime(&t, "at %I:%M %p"), "at 08:37 AM");
+        assert_eq!(strftime(&t, "%c"), "Mon Nov 19 08:37:48 2007");
+        assert_eq!(strftime(&t, "%F %T %z"), "2007-11-19 08:37:48 -0600");
+        assert_eq!(strftime(&t, "%D %R %r"), "11/19/07 08:37 08:37:48 AM");
+        assert_eq!(strftime(&t, "%v"), "19-NOV-2007");
+        assert_eq!(strftime(&t, "%j %U %W %u %w"), "323 46 47 1 1");
+        assert_eq!(strftime(&t, "%G %g %V"), "2007 07 47");
+        assert_eq!(strftime(&t, "%s"), "1195483068");
+        assert_eq!(strftime(&t, "%C %y %%"), "20 07 %");
+    }
+
+    #[test]
+    fn test_strftime_flags() {
+        let t = local_time!(2001,2,3,4,5,6,"+09:00");
+        assert_eq!(strftime(&t, "%-m/%-d %-H:%M"), "2/3 4:05");
+        assert_eq!(strftime(&t, "%_m/%e %k %l"), " 2/ 3  4  4");
+        assert_eq!(strftime(&t, "%0e %010Y"), "03 0000002001");
+        assert_eq!(strftime(&t, "%^a %^B %#b %10A"), "SAT FEBRUARY FEB   Saturday");
+        assert_eq!(strftime(&t, "%p %P %#p %^P"), "AM am am AM");
+        assert_eq!(strftime(&t, "%-y %5S %-5S %_5S"), "1 00006 6     6");
+    }
+
+    #[test]
+    fn test_strftime_fraction() {
+        let t = local_time!(2001,2,3,4,5,6).with_nanosecond(123_456_789).unwrap();
+        assert_eq!(strftime(&t, "%L %N"), "123 123456789");
+        assert_eq!(strftime(&t, "%3N %6N %12N %1L"), "123 123456 123456789000 1");
+    }
+
+    #[test]
+    fn test_strftime_offset() {
+        let t = local_time!(2001,2,3,4,5,6,"+09:00");
+        assert_eq!(strftime(&t, "%z %:z %::z %:::z"), "+0900 +09:00 +09:00:00 +09");
+        assert_eq!(strftime(&t, "%-z %_z %10z %_10z"), "+900  +900 +000000900       +900");
+        let t = local_time!(2001,2,3,4,5,6,"-05:30");
+        assert_eq!(strftime(&t, "%z %:z %:::z"), "-0530 -05:30 -05:30");
+        let t = utc_time!(2001,2,3,4,5,6);
+        assert_eq!(strftime(&t, "%z %Z %#Z %+"), "+0000 UTC utc Sat Feb  3 04:05:06 UTC 2001");
+    }
+
+    #[test]
+    fn test_strftime_year() {
+        assert_eq!(strftime(&utc_time!(1), "%Y"), "0001");
+        assert_eq!(strftime(&utc_time!(-1), "%Y %C %y"), "-0001 -1 99");
+        assert_eq!(strftime(&utc_time!(12345), "%Y"), "12345");
+    }
+
+    #[test]
+    fn test_strftime_unknown() {
+        let t = utc_time!(2001,2,3);
+        assert_eq!(strftime(&t, "%q %-q %:y 100%"), "%q %-q %:y 100%");
+        assert_eq!(strftime(&t, "%Ey %Od %Ez"), "01 03 %Ez");
+    }
+
+    #[test]
+    fn test_jisx0301() {
+        let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(to_jisx0301(&date(2001,2,3)), "H13.02.03");
+        assert_eq!(to_jisx0301(&date(2024,10,18)), "R06.10.18");
+        assert_eq!(to_jisx0301(&date(2019,4,30)), "H31.04.30");
+        assert_eq!(to_jisx0301(&date(2019,5,1)), "R01.05.01");
+        assert_eq!(to_jisx0301(&date(1989,1,7)), "S64.01.07");
+        assert_eq!(to_jisx0301(&date(1989,1,8)), "H01.01.08");
+        assert_eq!(to_jisx0301(&date(1926,12,25)), "S01.12.25");
+        assert_eq!(to_jisx0301(&date(1912,7,29)), "M45.07.29");
+        assert_eq!(to_jisx0301(&date(1912,7,30)), "T01.07.30");
+        assert_eq!(to_jisx0301(&date(1873,1,1)), "M06.01.01");
+        assert_eq!(to_jisx0301(&date(1872,12,31)), "1872-12-31");
+
+        let t = local_time!(2001,2,3,4,5,6,"+07:00").with_nanosecond(123456789).unwrap();
+        assert_eq!(to_jisx0301_time(&t, 0), "H13.02.03T04:05:06+07:00");
+        assert_eq!(to_jisx0301_time(&t, 3), "H13.02.03T04:05:06.123+07:00");
+        assert_eq!(to_jisx0301_time(&utc_time!(2024,10,18,9,30), 0), "R06.10.18T09:30:00+00:00");
+    }
+}
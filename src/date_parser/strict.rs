@@ -0,0 +1,778 @@
+use regex::{Captures, Regex, RegexBuilder};
+
+use crate::DateTime;
+use super::Ixdtf;
+use super::parse::{date_zone_to_diff, day_num, era_by_name, months_num, ERAS};
+
+// Strict parsers for the fixed formats Ruby exposes as Date._iso8601,
+// Date._rfc3339 and friends. Unlike date_parse they only accept the whole
+// string in exactly that format and return None otherwise. Digits are the
+// ASCII ones only, as \d is in Ruby.
+
+fn comp_year69(year: i32) -> i32 {
+    if year >= 69 {
+        year + 1900
+    } else {
+        year + 2000
+    }
+}
+
+// The obsolete two digit and three digit years of RFC 2822 section 4.3.
+pub(super) fn comp_year50(year: i32) -> i32 {
+    if year >= 50 {
+        year + 1900
+    } else {
+        year + 2000
+    }
+}
+
+fn str2num<T: std::str::FromStr>(caps: &Captures, i: usize) -> Option<T> {
+    caps.get(i).and_then(|x| x.as_str().parse::<T>().ok())
+}
+
+// Years written with less than four characters (sign included) are two
+// digit years and get completed.
+fn year_num(caps: &Captures, i: usize) -> Option<i32> {
+    let s = caps.get(i)?.as_str();
+    let year = s.parse::<i32>().ok()?;
+    if s.len() < 4 {
+        Some(comp_year69(year))
+    } else {
+        Some(year)
+    }
+}
+
+fn mon_num(caps: &Captures, i: usize) -> Option<u32> {
+    caps.get(i).and_then(|x| months_num(&x.as_str()[..3]).parse::<u32>().ok())
+}
+
+fn wday_num(caps: &Captures, i: usize) -> Option<i32> {
+    caps.get(i).map(|x| day_num(&x.as_str()[..3]))
+}
+
+fn set_sec_fraction(datetime: &mut DateTime, caps: &Captures, i: usize) {
+    if let Some(fraction_str) = caps.get(i) {
+        let frac_str = format!("0.{}", fraction_str.as_str());
+        if let Ok(fraction) = frac_str.parse::<f64>() {
+            datetime.sec_fraction = Some(fraction);
+        }
+    }
+}
+
+fn set_zone(datetime: &mut DateTime, caps: &Captures, i: usize) {
+    if let Some(zone) = caps.get(i) {
+        datetime.zone = Some(zone.as_str().to_owned());
+        datetime.offset = date_zone_to_diff(zone.as_str());
+    }
+}
+
+fn iso8601_ext_datetime(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref ISO8601_EXT_DATETIME_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            (?:
+                ([-+]?[0-9]{2,}|-)-([0-9]{2})?(?:-([0-9]{2}))?
+            |
+                ([-+]?[0-9]{2,})?-([0-9]{3})
+            |
+                ([0-9]{4}|[0-9]{2})?-w([0-9]{2})-([0-9])
+            |
+                -w-([0-9])
+            )
+            (?:
+                t
+                ([0-9]{2}):([0-9]{2})(?::([0-9]{2})(?:[,.]([0-9]+))?)?
+                (z|[-+][0-9]{2}(?::?[0-9]{2})?)?
+            )?
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = ISO8601_EXT_DATETIME_REGEX.captures(string)?;
+    let mut datetime = DateTime::default();
+
+    if let Some(year_str) = caps.get(1) {
+        if caps.get(3).is_some() {
+            datetime.mday = Some(str2num(&caps, 3)?);
+        }
+        if year_str.as_str() != "-" {
+            datetime.year = Some(year_num(&caps, 1)?);
+        }
+        if caps.get(2).is_none() {
+            if year_str.as_str() != "-" {
+                return None;
+            }
+        } else {
+            datetime.mon = Some(str2num(&caps, 2)?);
+        }
+    } else if caps.get(5).is_some() {
+        datetime.yday = Some(str2num(&caps, 5)?);
+        if caps.get(4).is_some() {
+            datetime.year = Some(year_num(&caps, 4)?);
+        }
+    } else if caps.get(8).is_some() {
+        datetime.cweek = Some(str2num(&caps, 7)?);
+        datetime.cwday = Some(str2num(&caps, 8)?);
+        if caps.get(6).is_some() {
+            datetime.cwyear = Some(year_num(&caps, 6)?);
+        }
+    } else if caps.get(9).is_some() {
+        datetime.cwday = Some(str2num(&caps, 9)?);
+    }
+
+    if caps.get(10).is_some() {
+        datetime.hour = Some(str2num(&caps, 10)?);
+        datetime.min = Some(str2num(&caps, 11)?);
+        if caps.get(12).is_some() {
+            datetime.sec = Some(str2num(&caps, 12)?);
+        }
+    }
+    set_sec_fraction(&mut datetime, &caps, 13);
+    set_zone(&mut datetime, &caps, 14);
+    Some(datetime)
+}
+
+fn iso8601_bas_datetime(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref ISO8601_BAS_DATETIME_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            (?:
+                ([-+]?(?:[0-9]{4}|[0-9]{2})|--)([0-9]{2}|-)([0-9]{2})
+            |
+                ([-+]?(?:[0-9]{4}|[0-9]{2}))([0-9]{3})
+            |
+                -([0-9]{3})
+            |
+                ([0-9]{4}|[0-9]{2})w([0-9]{2})([0-9])
+            |
+                -w([0-9]{2})([0-9])
+            |
+                -w-([0-9])
+            )
+            (?:
+                t?
+                ([0-9]{2})([0-9]{2})(?:([0-9]{2})(?:[,.]([0-9]+))?)?
+                (z|[-+][0-9]{2}(?:[0-9]{2})?)?
+            )?
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = ISO8601_BAS_DATETIME_REGEX.captures(string)?;
+    let mut datetime = DateTime::default();
+
+    if caps.get(3).is_some() {
+        let year_str = caps.get(1)?.as_str();
+        datetime.mday = Some(str2num(&caps, 3)?);
+        if year_str != "--" {
+            datetime.year = Some(year_num(&caps, 1)?);
+        }
+        if caps.get(2)?.as_str().starts_with('-') {
+            if year_str != "--" {
+                return None;
+            }
+        } else {
+            datetime.mon = Some(str2num(&caps, 2)?);
+        }
+    } else if caps.get(5).is_some() {
+        datetime.yday = Some(str2num(&caps, 5)?);
+        datetime.year = Some(year_num(&caps, 4)?);
+    } else if caps.get(6).is_some() {
+        datetime.yday = Some(str2num(&caps, 6)?);
+    } else if caps.get(9).is_some() {
+        datetime.cweek = Some(str2num(&caps, 8)?);
+        datetime.cwday = Some(str2num(&caps, 9)?);
+        datetime.cwyear = Some(year_num(&caps, 7)?);
+    } else if caps.get(11).is_some() {
+        datetime.cweek = Some(str2num(&caps, 10)?);
+        datetime.cwday = Some(str2num(&caps, 11)?);
+    } else if caps.get(12).is_some() {
+        datetime.cwday = Some(str2num(&caps, 12)?);
+    }
+
+    if caps.get(13).is_some() {
+        datetime.hour = Some(str2num(&caps, 13)?);
+        datetime.min = Some(str2num(&caps, 14)?);
+        if caps.get(15).is_some() {
+            datetime.sec = Some(str2num(&caps, 15)?);
+        }
+    }
+    set_sec_fraction(&mut datetime, &caps, 16);
+    set_zone(&mut datetime, &caps, 17);
+    Some(datetime)
+}
+
+fn iso8601_ext_time(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref ISO8601_EXT_TIME_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            ([0-9]{2}):([0-9]{2})
+            (?:
+                :([0-9]{2})(?:[,.]([0-9]+))?
+                (z|[-+][0-9]{2}(:?[0-9]{2})?)?
+            )?
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = ISO8601_EXT_TIME_REGEX.captures(string)?;
+    let mut datetime = DateTime {
+        hour: Some(str2num(&caps, 1)?),
+        min: Some(str2num(&caps, 2)?),
+        ..Default::default()
+    };
+    if caps.get(3).is_some() {
+        datetime.sec = Some(str2num(&caps, 3)?);
+    }
+    set_sec_fraction(&mut datetime, &caps, 4);
+    set_zone(&mut datetime, &caps, 5);
+    Some(datetime)
+}
+
+fn iso8601_bas_time(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref ISO8601_BAS_TIME_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            ([0-9]{2})([0-9]{2})
+            (?:
+                ([0-9]{2})(?:[,.]([0-9]+))?
+                (z|[-+][0-9]{2}([0-9]{2})?)?
+            )?
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = ISO8601_BAS_TIME_REGEX.captures(string)?;
+    let mut datetime = DateTime {
+        hour: Some(str2num(&caps, 1)?),
+        min: Some(str2num(&caps, 2)?),
+        ..Default::default()
+    };
+    if caps.get(3).is_some() {
+        datetime.sec = Some(str2num(&caps, 3)?);
+    }
+    set_sec_fraction(&mut datetime, &caps, 4);
+    set_zone(&mut datetime, &caps, 5);
+    Some(datetime)
+}
+
+/// Parses the given string as an ISO 8601 extended or basic format
+/// calendar date (2001-02-03), ordinal date (2001-034) or week date
+/// (2001-W05-6), or a bare time of day, each with an optional time and
+/// offset. This is the equivalent of Ruby's `Date._iso8601`.
+///
+/// Returns `None` if the whole string is not in one of those formats.
+///
+/// Date._iso8601('2001-02-03T04:05:06+07:00')
+///     #=> {:mday=>3, :year=>2001, :mon=>2, :hour=>4, :min=>5, :sec=>6, :zone=>"+07:00", :offset=>25200}
+pub fn iso8601(string: &str) -> Option<DateTime> {
+    iso8601_ext_datetime(string)
+        .or_else(|| iso8601_bas_datetime(string))
+        .or_else(|| iso8601_ext_time(string))
+        .or_else(|| iso8601_bas_time(string))
+}
+
+/// Parses the given string as an RFC 3339 date-time, which requires a full
+/// date, a `T` or space separator, a time with seconds and an explicit
+/// offset. This is the equivalent of Ruby's `Date._rfc3339`.
+///
+/// Returns `None` if the whole string is not in that format.
+///
+/// Date._rfc3339('2001-02-03T04:05:06+07:00')
+///     #=> {:year=>2001, :mon=>2, :mday=>3, :hour=>4, :min=>5, :sec=>6, :zone=>"+07:00", :offset=>25200}
+pub fn rfc3339(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref RFC3339_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            (-?[0-9]{4})-([0-9]{2})-([0-9]{2})
+            (?:t|\s)
+            ([0-9]{2}):([0-9]{2}):([0-9]{2})(?:\.([0-9]+))?
+            (z|[-+][0-9]{2}:[0-9]{2})
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = RFC3339_REGEX.captures(string)?;
+    let mut datetime = DateTime {
+        year: Some(str2num(&caps, 1)?),
+        mon: Some(str2num(&caps, 2)?),
+        mday: Some(str2num(&caps, 3)?),
+        hour: Some(str2num(&caps, 4)?),
+        min: Some(str2num(&caps, 5)?),
+        sec: Some(str2num(&caps, 6)?),
+        ..Default::default()
+    };
+    set_zone(&mut datetime, &caps, 8);
+    set_sec_fraction(&mut datetime, &caps, 7);
+    Some(datetime)
+}
+
+fn xmlschema_datetime(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref XMLSCHEMA_DATETIME_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            (-?[0-9]{4,})(?:-([0-9]{2})(?:-([0-9]{2}))?)?
+            (?:
+                t
+                ([0-9]{2}):([0-9]{2}):([0-9]{2})(?:\.([0-9]+))?
+            )?
+            (z|[-+][0-9]{2}:[0-9]{2})?
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = XMLSCHEMA_DATETIME_REGEX.captures(string)?;
+    let mut datetime = DateTime {
+        year: Some(str2num(&caps, 1)?),
+        ..Default::default()
+    };
+    if caps.get(2).is_some() {
+        datetime.mon = Some(str2num(&caps, 2)?);
+    }
+    if caps.get(3).is_some() {
+        datetime.mday = Some(str2num(&caps, 3)?);
+    }
+    if caps.get(4).is_some() {
+        datetime.hour = Some(str2num(&caps, 4)?);
+        datetime.min = Some(str2num(&caps, 5)?);
+        datetime.sec = Some(str2num(&caps, 6)?);
+    }
+    set_sec_fraction(&mut datetime, &caps, 7);
+    set_zone(&mut datetime, &caps, 8);
+    Some(datetime)
+}
+
+fn xmlschema_time(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref XMLSCHEMA_TIME_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            ([0-9]{2}):([0-9]{2}):([0-9]{2})(?:\.([0-9]+))?
+            (z|[-+][0-9]{2}:[0-9]{2})?
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = XMLSCHEMA_TIME_REGEX.captures(string)?;
+    let mut datetime = DateTime {
+        hour: Some(str2num(&caps, 1)?),
+        min: Some(str2num(&caps, 2)?),
+        sec: Some(str2num(&caps, 3)?),
+        ..Default::default()
+    };
+    set_sec_fraction(&mut datetime, &caps, 4);
+    set_zone(&mut datetime, &caps, 5);
+    Some(datetime)
+}
+
+// The truncated gMonthDay (--02-03), gMonth (--02) and gDay (---03) forms.
+fn xmlschema_trunc(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref XMLSCHEMA_TRUNC_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            (?:
+                --([0-9]{2})(?:-([0-9]{2}))?
+            |
+                ---([0-9]{2})
+            )
+            (z|[-+][0-9]{2}:[0-9]{2})?
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = XMLSCHEMA_TRUNC_REGEX.captures(string)?;
+    let mut datetime = DateTime::default();
+    if caps.get(1).is_some() {
+        datetime.mon = Some(str2num(&caps, 1)?);
+    }
+    if caps.get(2).is_some() {
+        datetime.mday = Some(str2num(&caps, 2)?);
+    }
+    if caps.get(3).is_some() {
+        datetime.mday = Some(str2num(&caps, 3)?);
+    }
+    set_zone(&mut datetime, &caps, 4);
+    Some(datetime)
+}
+
+/// Parses the given string as one of the XML Schema lexical forms:
+/// dateTime, date, gYearMonth and gYear (2001-02-03T04:05:06+07:00,
+/// 2001-02-03, 2001-02, 2001), time (04:05:06Z) or the truncated gMonthDay,
+/// gMonth and gDay forms (--02-03, --02, ---03). This is the equivalent of
+/// Ruby's `Date._xmlschema`.
+///
+/// Returns `None` if the whole string is not in one of those forms.
+///
+/// Date._xmlschema('2001-02-03') #=> {:year=>2001, :mon=>2, :mday=>3}
+pub fn xmlschema(string: &str) -> Option<DateTime> {
+    xmlschema_datetime(string)
+        .or_else(|| xmlschema_time(string))
+        .or_else(|| xmlschema_trunc(string))
+}
+
+/// Parses the given string as an RFC 2822 (and RFC 822) date-time such as
+/// `Sat, 3 Feb 2001 04:05:06 +0700`. Two and three digit years are
+/// completed as the RFC prescribes. This is the equivalent of Ruby's
+/// `Date._rfc2822`.
+///
+/// Returns `None` if the whole string is not in that format.
+///
+/// Date._rfc2822('Sat, 3 Feb 2001 04:05:06 +0700')
+///     #=> {:wday=>6, :mday=>3, :mon=>2, :year=>2001, :hour=>4, :min=>5, :sec=>6, :zone=>"+0700", :offset=>25200}
+pub fn rfc2822(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref RFC2822_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            (?:(sun|mon|tue|wed|thu|fri|sat)\s*,\s+)?
+            ([0-9]{1,2})\s+
+            (jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)\s+
+            (-?[0-9]{2,})\s+
+            ([0-9]{2}):([0-9]{2})(?::([0-9]{2}))?\s*
+            ([-+][0-9]{4}|ut|gmt|e[sd]t|c[sd]t|m[sd]t|p[sd]t|[a-ik-z])
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = RFC2822_REGEX.captures(string)?;
+    let year_str = caps.get(4)?.as_str();
+    let mut year = year_str.parse::<i32>().ok()?;
+    if year_str.len() < 4 {
+        year = comp_year50(year);
+    }
+    let mut datetime = DateTime {
+        wday: wday_num(&caps, 1),
+        mday: Some(str2num(&caps, 2)?),
+        mon: Some(mon_num(&caps, 3)?),
+        year: Some(year),
+        hour: Some(str2num(&caps, 5)?),
+        min: Some(str2num(&caps, 6)?),
+        ..Default::default()
+    };
+    if caps.get(7).is_some() {
+        datetime.sec = Some(str2num(&caps, 7)?);
+    }
+    set_zone(&mut datetime, &caps, 8);
+    Some(datetime)
+}
+
+// IMF-fixdate: Sun, 06 Nov 1994 08:49:37 GMT
+fn httpdate_type1(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref HTTPDATE_TYPE1_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            (sun|mon|tue|wed|thu|fri|sat)\s*,\s+
+            ([0-9]{2})\s+
+            (jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)\s+
+            (-?[0-9]{4})\s+
+            ([0-9]{2}):([0-9]{2}):([0-9]{2})\s+
+            (gmt)
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = HTTPDATE_TYPE1_REGEX.captures(string)?;
+    Some(DateTime {
+        wday: wday_num(&caps, 1),
+        mday: Some(str2num(&caps, 2)?),
+        mon: Some(mon_num(&caps, 3)?),
+        year: Some(str2num(&caps, 4)?),
+        hour: Some(str2num(&caps, 5)?),
+        min: Some(str2num(&caps, 6)?),
+        sec: Some(str2num(&caps, 7)?),
+        zone: Some(caps.get(8)?.as_str().to_owned()),
+        offset: Some(0),
+        ..Default::default()
+    })
+}
+
+// Obsolete RFC 850 format: Sunday, 06-Nov-94 08:49:37 GMT
+pub(super) fn httpdate_type2(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref HTTPDATE_TYPE2_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            (sunday|monday|tuesday|wednesday|thursday|friday|saturday)\s*,\s+
+            ([0-9]{2})\s*-\s*
+            (jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)\s*-\s*
+            ([0-9]{2})\s+
+            ([0-9]{2}):([0-9]{2}):([0-9]{2})\s+
+            (gmt)
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = HTTPDATE_TYPE2_REGEX.captures(string)?;
+    Some(DateTime {
+        wday: wday_num(&caps, 1),
+        mday: Some(str2num(&caps, 2)?),
+        mon: Some(mon_num(&caps, 3)?),
+        year: Some(comp_year69(str2num(&caps, 4)?)),
+        hour: Some(str2num(&caps, 5)?),
+        min: Some(str2num(&caps, 6)?),
+        sec: Some(str2num(&caps, 7)?),
+        zone: Some(caps.get(8)?.as_str().to_owned()),
+        offset: Some(0),
+        ..Default::default()
+    })
+}
+
+// ANSI C's asctime() format: Sun Nov  6 08:49:37 1994
+fn httpdate_type3(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref HTTPDATE_TYPE3_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            (sun|mon|tue|wed|thu|fri|sat)\s+
+            (jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)\s+
+            ([0-9]{1,2})\s+
+            ([0-9]{2}):([0-9]{2}):([0-9]{2})\s+
+            ([0-9]{4})
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = HTTPDATE_TYPE3_REGEX.captures(string)?;
+    Some(DateTime {
+        wday: wday_num(&caps, 1),
+        mon: Some(mon_num(&caps, 2)?),
+        mday: Some(str2num(&caps, 3)?),
+        hour: Some(str2num(&caps, 4)?),
+        min: Some(str2num(&caps, 5)?),
+        sec: Some(str2num(&caps, 6)?),
+        year: Some(str2num(&caps, 7)?),
+        ..Default::default()
+    })
+}
+
+/// Parses the given string as an HTTP-date in any of the three formats
+/// RFC 2616 allows: IMF-fixdate, the obsolete RFC 850 format and ANSI C's
+/// asctime() format. This is the equivalent of Ruby's `Date._httpdate`.
+///
+/// Returns `None` if the whole string is not in one of those formats.
+///
+/// Date._httpdate('Sat, 03 Feb 2001 04:05:06 GMT')
+///     #=> {:wday=>6, :mday=>3, :mon=>2, :year=>2001, :hour=>4, :min=>5, :sec=>6, :zone=>"GMT", :offset=>0}
+pub fn httpdate(string: &str) -> Option<DateTime> {
+    httpdate_type1(string)
+        .or_else(|| httpdate_type2(string))
+        .or_else(|| httpdate_type3(string))
+}
+
+// Whether the era relative date falls inside the era, so H31.04.30 is
+// accepted but H31.05.01 and H32.01.01 are not.
+fn era_contains(era_index: usize, year: i32, mon: u32, mday: u32) -> bool {
+    let era = &ERAS[era_index];
+    if year < 1 {
+        return false;
+    }
+    let date = match era.base.checked_add(year).and_then(|year| chrono::NaiveDate::from_ymd_opt(year, mon, mday)) {
+        Some(date) => date,
+        None => return false,
+    };
+    let start = |(y, m, d): (i32, u32, u32)| chrono::NaiveDate::from_ymd_opt(y, m, d);
+    start(era.start).is_some_and(|start| date >= start)
+        && ERAS.get(era_index + 1).and_then(|next| start(next.start)).is_none_or(|next| date < next)
+}
+
+/// Parses the given string as a JIS X 0301 date such as `H13.02.03`,
+/// optionally followed by a time and offset (`H13.02.03T04:05:06+07:00`).
+/// The era may be given by its initial (M, T, S, H, R), its full-width
+/// initial or its kanji name (明治, 大正, 昭和, 平成, 令和) and defaults to
+/// Heisei when omitted. Strings without an era fall back to `iso8601`.
+/// This is the equivalent of Ruby's `Date._jisx0301`.
+///
+/// Unlike Ruby the date has to fall inside the era: Heisei ends on
+/// 2019-04-30 (H31.04.30) and Reiwa starts on 2019-05-01 (R01.05.01), so
+/// `H32.01.01` and `R01.04.30` return `None`.
+///
+/// Date._jisx0301('H13.02.03T04:05:06+07:00')
+///     #=> {:year=>2001, :mon=>2, :mday=>3, :hour=>4, :min=>5, :sec=>6, :zone=>"+07:00", :offset=>25200}
+pub fn jisx0301(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref JISX0301_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            ([mtshr]|[ＭＴＳＨＲ]|明治|大正|昭和|平成|令和)?
+            ([0-9]{2})\.([0-9]{2})\.([0-9]{2})
+            (?:
+                t
+                (?:
+                    ([0-9]{2}):([0-9]{2})(?::([0-9]{2})(?:[,.]([0-9]*))?)?
+                    (z|[-+][0-9]{2}(?::?[0-9]{2})?)?
+                )?
+            )?
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let caps = match JISX0301_REGEX.captures(string) {
+        Some(caps) => caps,
+        None => return iso8601(string),
+    };
+    let era = caps.get(1).map_or(Some(&ERAS[3]), |x| era_by_name(x.as_str()))?;
+    let era_index = ERAS.iter().position(|x| std::ptr::eq(x, era))?;
+    let year = str2num::<i32>(&caps, 2)?;
+    let mon = str2num(&caps, 3)?;
+    let mday = str2num(&caps, 4)?;
+    if !era_contains(era_index, year, mon, mday) {
+        return None;
+    }
+    let mut datetime = DateTime {
+        year: Some(era.base + year),
+        mon: Some(mon),
+        mday: Some(mday),
+        hour: str2num(&caps, 5),
+        min: str2num(&caps, 6),
+        sec: str2num(&caps, 7),
+        ..Default::default()
+    };
+    set_sec_fraction(&mut datetime, &caps, 8);
+    set_zone(&mut datetime, &caps, 9);
+    Some(datetime)
+}
+
+// Whether the zone annotation is known and the offset of the timestamp
+// agrees with it. Z and -00:00 say the local offset is unknown, so they
+// agree with any known zone.
+fn ixdtf_zone_consistent(datetime: &DateTime, zone: &str) -> bool {
+    use chrono::{Offset, TimeZone};
+
+    let zone_offset = match zone.starts_with(['+', '-']) {
+        true => date_zone_to_diff(zone),
+        false => None,
+    };
+    let tz = match (zone_offset, zone.parse::<chrono_tz::Tz>()) {
+        (Some(_), _) => None,
+        (None, Ok(tz)) => Some(tz),
+        (None, Err(_)) => return false,
+    };
+    let offset = match (datetime.zone.as_deref(), datetime.offset) {
+        (Some("Z") | Some("z") | Some("-00:00"), _) | (_, None) => return true,
+        (_, Some(offset)) => offset,
+    };
+    let Some(tz) = tz else {
+        return zone_offset == Some(offset);
+    };
+    let instant = (|| {
+        let naive = chrono::NaiveDate::from_ymd_opt(datetime.year?, datetime.mon?, datetime.mday?)?
+            .and_hms_opt(datetime.hour?, datetime.min?, datetime.sec?.min(59))?;
+        naive.checked_sub_signed(chrono::Duration::seconds(offset as i64))
+    })();
+    instant.is_some_and(|instant| tz.offset_from_utc_datetime(&instant).fix().local_minus_utc() == offset)
+}
+
+/// Parses the given string as an RFC 9557 (IXDTF) timestamp, an RFC 3339
+/// date-time followed by optional suffixes: a time zone annotation
+/// (`[Europe/Paris]`, `[+01:00]`) first, then tags such as the calendar
+/// (`[u-ca=japanese]`). A suffix marked critical with `!` must be
+/// understood, so an unknown critical tag or zone returns `None`, as does a
+/// critical zone that disagrees with the offset (`Z` and `-00:00` agree
+/// with any known zone). An elective zone that disagrees is kept, the
+/// offset wins. The suffixes are returned in `ixdtf`.
+///
+/// Returns `None` if the whole string is not in that format.
+///
+/// rfc9557('2001-02-03T04:05:06+01:00[!Europe/Paris][u-ca=gregory]')
+///     #=> {:year=>2001, :mon=>2, :mday=>3, :hour=>4, :min=>5, :sec=>6, :zone=>"+01:00", :offset=>3600,
+///          :ixdtf=>{:zone=>"Europe/Paris", :zone_critical=>true, :calendar=>"gregory", :calendar_critical=>false}}
+pub fn rfc9557(string: &str) -> Option<DateTime> {
+    lazy_static! {
+        static ref IXDTF_SUFFIX_REGEX: Regex = RegexBuilder::new(r"\[(!)?([^\[\]]*)\]")
+            .build()
+            .unwrap();
+        static ref IXDTF_TAG_REGEX: Regex = RegexBuilder::new(r"\A([a-z_][a-z0-9_-]*)=([A-Za-z0-9]+(?:-[A-Za-z0-9]+)*)\z")
+            .case_insensitive(false)
+            .build()
+            .unwrap();
+        static ref IXDTF_ZONE_REGEX: Regex = RegexBuilder::new(r"\A(?:[+-][0-9]{2}:[0-9]{2}|[a-z._][a-z0-9._+-]*(?:/[a-z._][a-z0-9._+-]*)*)\z")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+    }
+    let string = string.trim();
+    let (stamp, suffixes) = string.split_at(string.find('[').unwrap_or(string.len()));
+    if stamp.ends_with(char::is_whitespace) {
+        return None;
+    }
+    let mut datetime = rfc3339(stamp)?;
+
+    let mut ixdtf = Ixdtf::default();
+    let mut rest = suffixes;
+    let mut first = true;
+    for caps in IXDTF_SUFFIX_REGEX.captures_iter(suffixes) {
+        let suffix = caps.get(0)?;
+        // The suffixes have to follow each other without anything between.
+        rest = rest.strip_prefix(suffix.as_str())?;
+        let critical = caps.get(1).is_some();
+        let content = caps.get(2)?.as_str();
+
+        if let Some(tag) = IXDTF_TAG_REGEX.captures(content) {
+            match &tag[1] {
+                "u-ca" => {
+                    if ixdtf.calendar.is_none() {
+                        ixdtf.calendar = Some(tag[2].to_owned());
+                        ixdtf.calendar_critical = critical;
+                    } else if critical || ixdtf.calendar_critical {
+                        // Conflicting calendars can't be ignored once one is critical.
+                        if ixdtf.calendar.as_deref() != Some(&tag[2]) {
+                            return None;
+                        }
+                    }
+                }
+                _ if critical => return None,
+                _ => {}
+            }
+        } else if first && IXDTF_ZONE_REGEX.is_match(content) {
+            if critical && !ixdtf_zone_consistent(&datetime, content) {
+                return None;
+            }
+            ixdtf.zone = Some(content.to_owned());
+            ixdtf.zone_critical = critical;
+        } else {
+            return None;
+        }
+        first = false;
+    }
+    if !rest.is_empty() {
+        return None;
+    }
+    if !suffixes.is_empty() {
+        datetime.ixdtf = Some(ixdtf);
+    }
+    Some(datetime)
+}
@@ -0,0 +1,401 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::DateTime;
+use super::parse::date_zone_to_diff;
+
+// Full names come first so that "Sunday" is consumed entirely before
+// "Sun" gets a chance to match, the same order Ruby's date_strptime.c uses.
+const DAY_NAMES: [&str; 14] = [
+    "Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday",
+    "Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat",
+];
+
+const MONTH_NAMES: [&str; 24] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun",
+    "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+// Intermediate state that only lives while a format is being matched.
+// `cent` and `merid` correspond to the `_cent` and `_merid` keys Ruby
+// stores in the hash and deletes before returning it.
+#[derive(Default)]
+struct Strptime {
+    datetime: DateTime,
+    cent: Option<i64>,
+    merid: Option<u32>,
+}
+
+// Returns true if the format element starting at `fmt` is numeric, in which
+// case the preceding directive must not swallow an unbounded run of digits.
+fn num_pattern_p(fmt: &[u8]) -> bool {
+    let mut i = 0;
+    match fmt.first() {
+        Some(c) if c.is_ascii_digit() => return true,
+        Some(b'%') => i += 1,
+        _ => return false,
+    }
+    if matches!(fmt.get(i), Some(b'E') | Some(b'O')) {
+        i += 1;
+    }
+    match fmt.get(i) {
+        Some(c) => b"CDdeFGgHIjkLlMmNQRrSsTUuVvWwXxYy".contains(c) || c.is_ascii_digit(),
+        None => false,
+    }
+}
+
+fn read_digits(string: &[u8], si: &mut usize, width: usize) -> Option<(i64, usize)> {
+    let start = *si;
+    let mut end = start;
+    while end < string.len() && end - start < width && string[end].is_ascii_digit() {
+        end += 1;
+    }
+    if end == start {
+        return None;
+    }
+    let digits = std::str::from_utf8(&string[start..end]).ok()?;
+    let n = digits.parse::<i64>().ok()?;
+    *si = end;
+    Some((n, end - start))
+}
+
+fn read_signed_digits(string: &[u8], si: &mut usize, width: usize) -> Option<i64> {
+    let mut sign = 1;
+    if let Some(c) = string.get(*si) {
+        if *c == b'+' || *c == b'-' {
+            if *c == b'-' {
+                sign = -1;
+            }
+            *si += 1;
+        }
+    }
+    read_digits(string, si, width).map(|(n, _)| sign * n)
+}
+
+// %d, %e, %H, %k, %I and %l accept a single space in place of the leading zero.
+fn read_padded_digits(string: &[u8], si: &mut usize) -> Option<i64> {
+    if string.get(*si) == Some(&b' ') {
+        *si += 1;
+        read_digits(string, si, 1).map(|(n, _)| n)
+    } else {
+        read_digits(string, si, 2).map(|(n, _)| n)
+    }
+}
+
+fn in_range(n: i64, min: i64, max: i64) -> Option<i64> {
+    if (min..=max).contains(&n) {
+        Some(n)
+    } else {
+        None
+    }
+}
+
+fn read_name(string: &[u8], si: &mut usize, names: &[&str]) -> Option<usize> {
+    for (i, name) in names.iter().enumerate() {
+        let len = name.len();
+        if let Some(candidate) = string.get(*si..(*si + len)) {
+            if candidate.eq_ignore_ascii_case(name.as_bytes()) {
+                *si += len;
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+fn read_zone(input: &str, si: &mut usize) -> Option<String> {
+    lazy_static! {
+        static ref ZONE_REGEX: Regex = RegexBuilder::new(r"
+            \A(
+                (?:gmt|utc?)?[-+]\d+(?:[,.:]\d+(?::\d+)?)?
+                |
+                (?-i:[[:alpha:].\s]+)(?:standard|daylight)\s+time\b
+                |
+                (?-i:[[:alpha:]]+)(?:\s+dst)?\b
+            )
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let rest = input.get(*si..)?;
+    let zone = ZONE_REGEX.captures(rest)?.get(1)?;
+    *si += zone.end();
+    Some(zone.as_str().to_owned())
+}
+
+fn date_strptime_internal(input: &str, si: &mut usize, format: &[u8], state: &mut Strptime) -> Option<()> {
+    let string = input.as_bytes();
+    let mut fi = 0;
+
+    while fi < format.len() {
+        let c = format[fi];
+        if c.is_ascii_whitespace() {
+            while string.get(*si).map(|c| c.is_ascii_whitespace()).unwrap_or_default() {
+                *si += 1;
+            }
+            fi += 1;
+            continue;
+        }
+        if c != b'%' || fi + 1 >= format.len() {
+            if string.get(*si) != Some(&c) {
+                return None;
+            }
+            *si += 1;
+            fi += 1;
+            continue;
+        }
+
+        fi += 1;
+        let mut directive = format[fi];
+        // %E and %O are locale modifiers; only the combinations Ruby knows are honoured.
+        if directive == b'E' || directive == b'O' {
+            let allowed: &[u8] = if directive == b'E' { b"cCxXyY" } else { b"deHkIlmMSuUVwWy" };
+            match format.get(fi + 1) {
+                Some(next) if allowed.contains(next) => {
+                    fi += 1;
+                    directive = format[fi];
+                }
+                _ => {
+                    if string.get(*si..(*si + 2)) != Some(&[b'%', directive]) {
+                        return None;
+                    }
+                    *si += 2;
+                    fi += 1;
+                    continue;
+                }
+            }
+        }
+        // %:z, %::z and %:::z parse exactly like %z.
+        if directive == b':' {
+            let mut i = 1;
+            while i < 3 && format.get(fi + i) == Some(&b':') {
+                i += 1;
+            }
+            if format.get(fi + i) != Some(&b'z') {
+                return None;
+            }
+            fi += i;
+            directive = b'z';
+        }
+        fi += 1;
+        let next_is_num = num_pattern_p(&format[fi..]);
+        let datetime = &mut state.datetime;
+
+        match directive {
+            b'A' | b'a' => {
+                let i = read_name(string, si, &DAY_NAMES)?;
+                datetime.wday = Some((i % 7) as i32);
+            },
+            b'B' | b'b' | b'h' => {
+                let i = read_name(string, si, &MONTH_NAMES)?;
+                datetime.mon = Some((i % 12) as u32 + 1);
+            },
+            b'C' => {
+                let width = if next_is_num { 2 } else { usize::MAX };
+                state.cent = Some(read_signed_digits(string, si, width)?);
+            },
+            b'c' => date_strptime_internal(input, si, b"%a %b %e %H:%M:%S %Y", state)?,
+            b'D' | b'x' => date_strptime_internal(input, si, b"%m/%d/%y", state)?,
+            b'd' | b'e' => {
+                let n = in_range(read_padded_digits(string, si)?, 1, 31)?;
+                datetime.mday = Some(n as u32);
+            },
+            b'F' => date_strptime_internal(input, si, b"%Y-%m-%d", state)?,
+            b'G' => {
+                let width = if next_is_num { 4 } else { usize::MAX };
+                let n = read_signed_digits(string, si, width)?;
+                datetime.cwyear = Some(i32::try_from(n).ok()?);
+            },
+            b'g' => {
+                let (n, _) = read_digits(string, si, 2)?;
+                let n = in_range(n, 0, 99)?;
+                datetime.cwyear = Some(n as i32);
+                if state.cent.is_none() {
+                    state.cent = Some(if n >= 69 { 19 } else { 20 });
+                }
+            },
+            b'H' | b'k' => {
+                let n = in_range(read_padded_digits(string, si)?, 0, 24)?;
+                datetime.hour = Some(n as u32);
+            },
+            b'I' | b'l' => {
+                let n = in_range(read_padded_digits(string, si)?, 1, 12)?;
+                datetime.hour = Some(n as u32);
+            },
+            b'j' => {
+                let (n, _) = read_digits(string, si, 3)?;
+                datetime.yday = Some(in_range(n, 1, 366)? as i32);
+            },
+            b'L' | b'N' => {
+                let mut sign = 1.0;
+                if let Some(c) = string.get(*si) {
+                    if *c == b'+' || *c == b'-' {
+                        if *c == b'-' {
+                            sign = -1.0;
+                        }
+                        *si += 1;
+                    }
+                }
+                let width = match (next_is_num, directive) {
+                    (true, b'L') => 3,
+                    (true, _) => 9,
+                    _ => usize::MAX,
+                };
+                let start = *si;
+                while *si < string.len() && *si - start < width && string[*si].is_ascii_digit() {
+                    *si += 1;
+                }
+                if *si == start {
+                    return None;
+                }
+                let frac_str = format!("0.{}", std::str::from_utf8(&string[start..*si]).ok()?);
+                datetime.sec_fraction = Some(sign * frac_str.parse::<f64>().ok()?);
+            },
+            b'M' => {
+                let (n, _) = read_digits(string, si, 2)?;
+                datetime.min = Some(in_range(n, 0, 59)? as u32);
+            },
+            b'm' => {
+                let (n, _) = read_digits(string, si, 2)?;
+                datetime.mon = Some(in_range(n, 1, 12)? as u32);
+            },
+            b'n' | b't' => date_strptime_internal(input, si, b" ", state)?,
+            b'P' | b'p' => {
+                let hour = match string.get(*si) {
+                    Some(b'P') | Some(b'p') => 12,
+                    Some(b'A') | Some(b'a') => 0,
+                    _ => return None,
+                };
+                let mut c = string.get(*si + 1).copied();
+                if c == Some(b'.') {
+                    // "a.m." / "p.m."
+                    if string.get(*si + 3) != Some(&b'.') {
+                        return None;
+                    }
+                    *si += 2;
+                    c = string.get(*si).copied();
+                }
+                if !matches!(c, Some(b'M') | Some(b'm')) {
+                    return None;
+                }
+                *si += 2;
+                state.merid = Some(hour);
+            },
+            b'Q' => {
+                let n = read_signed_digits(string, si, usize::MAX)?;
+                datetime.seconds = Some(n as f64 / 1000.0);
+            },
+            b'R' => date_strptime_internal(input, si, b"%H:%M", state)?,
+            b'r' => date_strptime_internal(input, si, b"%I:%M:%S %p", state)?,
+            b'S' => {
+                let (n, _) = read_digits(string, si, 2)?;
+                datetime.sec = Some(in_range(n, 0, 60)? as u32);
+            },
+            b's' => {
+                let n = read_signed_digits(string, si, usize::MAX)?;
+                datetime.seconds = Some(n as f64);
+            },
+            b'T' | b'X' => date_strptime_internal(input, si, b"%H:%M:%S", state)?,
+            b'U' => {
+                let (n, _) = read_digits(string, si, 2)?;
+                datetime.wnum0 = Some(in_range(n, 0, 53)? as u32);
+            },
+            b'u' => {
+                let (n, _) = read_digits(string, si, 1)?;
+                datetime.cwday = Some(in_range(n, 1, 7)? as u32);
+            },
+            b'V' => {
+                let (n, _) = read_digits(string, si, 2)?;
+                datetime.cweek = Some(in_range(n, 1, 53)? as u32);
+            },
+            b'v' => date_strptime_internal(input, si, b"%e-%b-%Y", state)?,
+            b'W' => {
+                let (n, _) = read_digits(string, si, 2)?;
+                datetime.wnum1 = Some(in_range(n, 0, 53)? as u32);
+            },
+            b'w' => {
+                let (n, _) = read_digits(string, si, 1)?;
+                datetime.wday = Some(in_range(n, 0, 6)? as i32);
+            },
+            b'Y' => {
+                let width = if next_is_num { 4 } else { usize::MAX };
+                let n = read_signed_digits(string, si, width)?;
+                datetime.year = Some(i32::try_from(n).ok()?);
+            },
+            b'y' => {
+                let (n, _) = read_digits(string, si, 2)?;
+                let n = in_range(n, 0, 99)?;
+                datetime.year = Some(n as i32);
+                if state.cent.is_none() {
+                    state.cent = Some(if n >= 69 { 19 } else { 20 });
+                }
+            },
+            b'Z' | b'z' => {
+                let zone = read_zone(input, si)?;
+                datetime.offset = date_zone_to_diff(&zone);
+                datetime.zone = Some(zone);
+            },
+            b'%' => {
+                if string.get(*si) != Some(&b'%') {
+                    return None;
+                }
+                *si += 1;
+            },
+            b'+' => date_strptime_internal(input, si, b"%a %b %e %H:%M:%S %Z %Y", state)?,
+            _ => {
+                // Unknown directives are matched literally, including the '%'.
+                if string.get(*si..(*si + 2)) != Some(&[b'%', directive]) {
+                    return None;
+                }
+                *si += 2;
+            },
+        }
+    }
+    Some(())
+}
+
+/// Parses the given representation of date and time with the given
+/// template, and returns the parsed elements along with any part of the
+/// string that was left over. The directives are those of Ruby's
+/// `Date._strptime`.
+///
+/// Returns `None` if the string does not match the format.
+pub fn date_strptime2(string: &str, format: &str) -> Option<(DateTime, String)> {
+    let mut state = Strptime::default();
+    let mut si = 0;
+    date_strptime_internal(string, &mut si, format.as_bytes(), &mut state)?;
+
+    let mut datetime = state.datetime;
+    if let Some(cent) = state.cent {
+        let cent = i32::try_from(cent).ok()?.checked_mul(100)?;
+        if let Some(cwyear) = datetime.cwyear {
+            datetime.cwyear = Some(cwyear.checked_add(cent)?);
+        }
+        if let Some(year) = datetime.year {
+            datetime.year = Some(year.checked_add(cent)?);
+        }
+    }
+    if let Some(merid) = state.merid {
+        if let Some(hour) = datetime.hour {
+            datetime.hour = Some(hour % 12 + merid);
+        }
+    }
+
+    let leftover = String::from_utf8_lossy(&string.as_bytes()[si..]).into_owned();
+    Some((datetime, leftover))
+}
+
+/// Parses the given representation of date and time with the given
+/// template. Unlike `date_strptime2`, the whole string has to be consumed
+/// by the format.
+///
+/// Date._strptime('2001-02-03', '%Y-%m-%d') #=> {:year=>2001, :mon=>2, :mday=>3}
+pub fn date_strptime(string: &str, format: &str) -> Option<DateTime> {
+    match date_strptime2(string, format) {
+        Some((datetime, leftover)) if leftover.is_empty() => Some(datetime),
+        _ => None,
+    }
+}
@@ -0,0 +1,63 @@
+use crate::date_parser::{date_parse, parse_candidates, DateOrder, DateTime};
+
+#[test]
+pub fn test_candidates_ambiguous() {
+    let candidates = parse_candidates("03/04/2024");
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0].datetime, DateTime{year: Some(2024), mon: Some(4), mday: Some(3), ..Default::default()});
+    assert_eq!(candidates[0].parser, "parse_sla");
+    assert_eq!(candidates[0].date_order, None);
+    assert_eq!(candidates[0].confidence, 1.0);
+    assert_eq!(candidates[1].datetime, DateTime{year: Some(2024), mon: Some(3), mday: Some(4), ..Default::default()});
+    assert_eq!(candidates[1].parser, "parse_sla");
+    assert_eq!(candidates[1].date_order, Some(DateOrder::Mdy));
+    assert_eq!(candidates[1].confidence, 0.9);
+
+    let candidates = parse_candidates("03.04.05");
+    let dates: Vec<_> = candidates.iter().take(3).map(|x| (x.datetime.year, x.datetime.mon, x.datetime.mday)).collect();
+    assert_eq!(dates, vec![(Some(2003), Some(4), Some(5)), (Some(2005), Some(3), Some(4)), (Some(2005), Some(4), Some(3))]);
+    assert!(candidates.iter().take(3).all(|x| x.parser == "parse_dot"));
+    //# parse_ddd reads 03.04 as seconds
+    assert_eq!(candidates[3].parser, "parse_ddd");
+    assert_eq!(candidates[3].datetime.sec, Some(3));
+    assert!(candidates[3].confidence < 0.9);
+}
+
+#[test]
+pub fn test_candidates_first_is_date_parse() {
+    for input in ["Sat Aug 28 02:55:50 1999", "1999-08-28", "H11.05.23", "19990828", "28/Aug/1999", "10:00 pm", "Aug 1999", "Dec 31 '99 10:00 EST"] {
+        let candidates = parse_candidates(input);
+        assert!(!candidates.is_empty(), "{}", input);
+        assert_eq!(candidates[0].datetime, date_parse(input, true), "{}", input);
+    }
+}
+
+#[test]
+pub fn test_candidates_implausible() {
+    //# 13 can only be the day
+    let candidates = parse_candidates("13/04/2024");
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].datetime, DateTime{year: Some(2024), mon: Some(4), mday: Some(13), ..Default::default()});
+
+    //# the day doesn't fit the month
+    let candidates = parse_candidates("02/30/2024");
+    assert!(candidates.iter().all(|x| x.datetime.mon != Some(2)));
+
+    assert!(parse_candidates("").is_empty());
+    assert!(parse_candidates("hello world").is_empty());
+}
+
+#[test]
+pub fn test_candidates_other_parsers() {
+    //# parse_sla would read the year first
+    let candidates = parse_candidates("2024/03/04 2024.04.03");
+    assert_eq!(candidates[0].parser, "parse_sla");
+    assert_eq!(candidates[0].datetime, DateTime{year: Some(2024), mon: Some(3), mday: Some(4), ..Default::default()});
+    assert!(candidates.iter().any(|x| x.parser == "parse_dot" && x.datetime == DateTime{year: Some(2024), mon: Some(4), mday: Some(3), ..Default::default()}));
+    assert!(candidates.iter().all(|x| x.confidence < 1.0));
+
+    let candidates = parse_candidates("10:00");
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].parser, "parse_time");
+    assert_eq!(candidates[0].confidence, 1.0);
+}
@@ -0,0 +1,84 @@
+use chrono::NaiveDate;
+
+use crate::date_parser::{parse_date_with_today, Date};
+use crate::ErrorKind;
+
+fn ymd(year: i32, mon: u32, mday: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, mon, mday).unwrap()
+}
+
+#[test]
+pub fn test_parse_date() {
+    let today = ymd(2001, 11, 29);
+    let parse = |x| parse_date_with_today(x, today);
+    assert_eq!(Ok(ymd(2001, 2, 3)), parse("2001-02-03"));
+    //# the time and zone are disregarded
+    assert_eq!(Ok(ymd(2001, 2, 3)), parse("2001-02-03T23:30:00-05:00"));
+    assert_eq!(Ok(ymd(2021, 8, 28)), parse("Sat Aug 28 02:55:50 +09:00 2021"));
+    assert_eq!(Ok(ymd(1999, 12, 31)), parse("31/12/1999"));
+    assert_eq!(Ok(ymd(2001, 2, 3)), parse("H13.02.03"));
+    assert_eq!(Ok(ymd(2001, 2, 3)), parse("2001-034"));
+    assert_eq!(Ok(ymd(2001, 2, 3)), parse("2001-W05-6"));
+}
+
+#[test]
+pub fn test_parse_date_completion() {
+    let today = ymd(2001, 11, 29);
+    let parse = |x| parse_date_with_today(x, today);
+    assert_eq!(Ok(ymd(2001, 2, 3)), parse("Feb 3"));
+    assert_eq!(Ok(ymd(2001, 2, 1)), parse("Feb"));
+    assert_eq!(Ok(ymd(1999, 2, 1)), parse("Feb 1999"));
+    assert_eq!(Ok(ymd(2001, 11, 3)), parse("3rd"));
+    assert_eq!(Ok(ymd(2001, 3, 5)), parse("W10"));
+    assert_eq!(Ok(ymd(2001, 3, 7)), parse("w10-3"));
+    //# the week of today, Sunday to Saturday
+    assert_eq!(Ok(ymd(2001, 11, 30)), parse("Fri 10:00"));
+    assert_eq!(Ok(ymd(2001, 11, 25)), parse("Sunday"));
+    //# a date wins over the day of the week
+    assert_eq!(Ok(ymd(2001, 11, 7)), parse("Fri Nov 7"));
+}
+
+#[test]
+pub fn test_parse_date_errors() {
+    let today = ymd(2001, 11, 29);
+    let parse = |x| parse_date_with_today(x, today).map_err(|e| e.kind());
+    assert_eq!(Err(ErrorKind::NoDateFound), parse("10:00"));
+    assert_eq!(Err(ErrorKind::NoDateFound), parse("foo"));
+    assert_eq!(Err(ErrorKind::InvalidDayOfMonth), parse("2001-02-29"));
+    assert_eq!(Err(ErrorKind::FieldOutOfRange), parse("2001-W53"));
+    let err = parse_date_with_today("2001-02-29", today).unwrap_err();
+    assert_eq!((err.field(), err.span(), err.input()), (Some("mday"), Some(0..10), Some("2001-02-29")));
+}
+
+#[test]
+pub fn test_date() {
+    let date = Date::new(2001, 1, 31).unwrap();
+    assert_eq!((2001, 1, 31, 3, 31), (date.year(), date.mon(), date.mday(), date.wday(), date.yday()));
+    assert_eq!((2001, 5, 3), (date.cwyear(), date.cweek(), date.cwday()));
+    assert_eq!(2451941, date.jd());
+    assert_eq!(2451545, Date::new(2000, 1, 1).unwrap().jd());
+    assert!(!date.leap());
+    assert_eq!("2001-01-31", date.to_string());
+    assert_eq!(Date::new(2001, 2, 28), Some(date >> 1));
+    assert_eq!(Date::new(2000, 11, 30), Some(date << 2));
+    assert_eq!(Date::new(2000, 2, 29), Date::new(2000, 2, 29).map(|x| x.next_year(4).prev_year(4)));
+    assert_eq!(Date::new(2001, 2, 1), Some(date + 1));
+    assert_eq!(Date::new(2001, 1, 30), Some(date.prev_day(1)));
+    assert_eq!(31, Date::new(2001, 3, 3).unwrap() - Date::new(2001, 1, 31).unwrap());
+    assert_eq!("Wed Jan 31 2001", date.strftime("%a %b %d %Y"));
+    assert_eq!(NaiveDate::from(date), ymd(2001, 1, 31));
+    assert_eq!(Ok(date), Date::parse_with_today("Jan 31", Date::new(2001, 6, 1).unwrap()));
+}
+
+#[test]
+pub fn test_parse_date_relative() {
+    let today = ymd(2001, 11, 29);
+    let parse = |x| parse_date_with_today(x, today);
+    assert_eq!(Ok(ymd(2001, 11, 30)), parse("tomorrow"));
+    assert_eq!(Ok(ymd(2001, 11, 26)), parse("3 days ago"));
+    assert_eq!(Ok(ymd(2001, 11, 30)), parse("next Friday at 10:00"));
+    assert_eq!(Ok(ymd(2001, 10, 1)), parse("last month"));
+    assert_eq!(Ok(ymd(2025, 3, 11)), parse("second Tuesday of March 2025"));
+    assert_eq!(Ok(ymd(2001, 11, 30)), parse("end of month"));
+    assert_eq!(Err(ErrorKind::FieldOutOfRange), parse("fifth Monday of Feb 2025").map_err(|e| e.kind()));
+}
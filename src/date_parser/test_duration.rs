@@ -0,0 +1,68 @@
+use chrono::DateTime;
+
+use crate::date_parser::{parse_duration, CalendarDuration};
+use crate::ErrorKind;
+
+fn duration(years: i64, months: i64, days: i64, hours: i64, minutes: i64, seconds: i64) -> CalendarDuration {
+    CalendarDuration { years, months, days, hours, minutes, seconds, sec_fraction: 0.0 }
+}
+
+#[test]
+pub fn test_parse_duration_iso8601() {
+    assert_eq!(Ok(duration(1, 2, 10, 2, 30, 0)), parse_duration("P1Y2M10DT2H30M"));
+    assert_eq!(Ok(duration(0, 0, 21, 0, 0, 0)), parse_duration("P3W"));
+    assert_eq!(Ok(duration(0, 0, 0, 0, 1, 0)), parse_duration("PT1M"));
+    assert_eq!(Ok(duration(0, 0, -1, 0, 0, 0)), parse_duration("-P1D"));
+    assert_eq!(Ok(duration(0, 0, 0, 1, 30, 0)), parse_duration("PT1.5H"));
+    assert_eq!(Ok(CalendarDuration { seconds: 1, sec_fraction: 0.25, ..Default::default() }), parse_duration("PT1,25S"));
+    //# alternative form
+    assert_eq!(Ok(duration(1, 2, 10, 2, 30, 0)), parse_duration("P0001-02-10T02:30:00"));
+    assert_eq!(Ok(duration(1, 2, 10, 2, 30, 0)), parse_duration("P00010210T023000"));
+    assert_eq!(Ok(duration(0, 3, 0, 0, 0, 0)), parse_duration("P0000-03-00"));
+}
+
+#[test]
+pub fn test_parse_duration_units() {
+    //# PostgreSQL
+    assert_eq!(Ok(duration(0, 0, 1, 2, 0, 0)), parse_duration("1 day 02:00:00"));
+    assert_eq!(Ok(duration(1, 2, 10, 2, 30, 0)), parse_duration("1 year 2 mons 10 days 02:30:00"));
+    assert_eq!(Ok(duration(0, 0, 1, -2, 0, 0)), parse_duration("1 day -02:00"));
+    assert_eq!(Ok(duration(0, 0, -3, -4, 0, 0)), parse_duration("3 days 4 hours ago"));
+    //# compact
+    assert_eq!(Ok(duration(0, 0, 0, 1, 30, 0)), parse_duration("1h30m"));
+    assert_eq!(Ok(duration(0, 0, 0, 0, 0, 90)), parse_duration("90s"));
+    assert_eq!(Ok(duration(0, 0, 2, 4, 0, 0)), parse_duration("2d 4h"));
+    assert_eq!(Ok(duration(0, 0, 0, 1, 30, 0)), parse_duration("1.5h"));
+    assert_eq!(Ok(duration(0, 0, 14, 0, 0, 0)), parse_duration("2 weeks"));
+}
+
+#[test]
+pub fn test_parse_duration_errors() {
+    for input in ["", "P", "PT", "P1.5Y", "P1.5DT2H", "1.5 months", "1 monday", "ago", "2 days ago 3h", "10", "P٠٠٠١-02-10", "P1٥D", "PT١H", "٣ days", "1:٣٠"] {
+        assert_eq!(Err(ErrorKind::InvalidDuration), parse_duration(input).map_err(|e| e.kind()), "{}", input);
+    }
+    let err = parse_duration("1h 30 parsecs").unwrap_err();
+    assert_eq!((err.span(), err.to_string()), (Some(2..13), "invalid duration at 2..13 in `1h 30 parsecs'".to_owned()));
+    let err = parse_duration("P0001-13-00").unwrap_err();
+    assert_eq!((err.kind(), err.field(), err.allowed()), (ErrorKind::FieldOutOfRange, Some("months"), Some(0..=12)));
+}
+
+#[test]
+pub fn test_calendar_duration() {
+    let t = DateTime::parse_from_rfc3339("2001-01-31T10:00:00+09:00").unwrap();
+    let since = |x| parse_duration(x).unwrap().since(t).unwrap().to_rfc3339();
+    assert_eq!("2001-02-28T10:00:00+09:00", since("P1M"));
+    assert_eq!("2001-03-01T11:30:00+09:00", since("P1M1DT1H30M"));
+    assert_eq!("2001-01-31T10:00:01.500+09:00", since("PT1.5S"));
+    assert_eq!("2000-12-31T10:00:00+09:00", parse_duration("P1M").unwrap().ago(t).unwrap().to_rfc3339());
+
+    //# keeps the time of day across a DST change
+    let ny = chrono_tz::America::New_York;
+    let t = DateTime::parse_from_rfc3339("2024-03-09T12:00:00-05:00").unwrap().with_timezone(&ny);
+    assert_eq!("2024-03-10T12:00:00-04:00", parse_duration("P1D").unwrap().since(t).unwrap().to_rfc3339());
+    assert_eq!("2024-03-10T13:00:00-04:00", parse_duration("PT24H").unwrap().since(t).unwrap().to_rfc3339());
+
+    for input in ["P1Y2M10DT2H30M", "PT0S", "-P1D", "PT1.25S", "P21D"] {
+        assert_eq!(input, parse_duration(input).unwrap().to_string());
+    }
+}
@@ -0,0 +1,57 @@
+use crate::date_parser::{find_dates, DateTime};
+
+#[test]
+pub fn test_find_dates() {
+    let text = "shipped Jan 3, delivered Fri Jan 7 at 10:00";
+    let found: Vec<_> = find_dates(text).collect();
+    assert_eq!(found.len(), 2);
+    assert_eq!(&text[found[0].range()], "Jan 3");
+    assert_eq!(found[0].datetime, DateTime{mon: Some(1), mday: Some(3), ..Default::default()});
+    assert_eq!(&text[found[1].range()], "Fri Jan 7 at 10:00");
+    assert_eq!(found[1].datetime, DateTime{wday: Some(5), mon: Some(1), mday: Some(7), hour: Some(10), min: Some(0), ..Default::default()});
+}
+
+#[test]
+pub fn test_find_dates_order() {
+    //# date_parse reads Jan 7 first, being parse_us
+    let text = "from 2024-01-03 to Jan 7, 2024";
+    let found: Vec<_> = find_dates(text).map(|x| text[x.range()].to_owned()).collect();
+    assert_eq!(found, vec!["2024-01-03", "Jan 7, 2024"]);
+
+    let text = "[2024-10-18 09:30:00 +0900] worker 3 started; retry at 10:15 UTC on 10/19";
+    let found: Vec<_> = find_dates(text).collect();
+    assert_eq!(found.len(), 2);
+    assert_eq!(&text[found[0].range()], "2024-10-18 09:30:00 +0900");
+    assert_eq!(found[0].datetime.offset, Some(9 * 3600));
+    assert_eq!(&text[found[1].range()], "10:15 UTC on 10/19");
+    assert_eq!(found[1].datetime, DateTime{hour: Some(10), min: Some(15), mon: Some(10), mday: Some(19), zone: Some("UTC".to_owned()), offset: Some(0), ..Default::default()});
+}
+
+#[test]
+pub fn test_find_dates_prose() {
+    assert_eq!(find_dates("We decided to monitor order 12 in the market.").count(), 0);
+    assert_eq!(find_dates("").count(), 0);
+
+    let text = "Meeting on Monday at 3pm, moved from 12 March 2025. See ticket 20250312.";
+    let found: Vec<_> = find_dates(text).map(|x| text[x.range()].to_owned()).collect();
+    assert_eq!(found, vec!["Monday at 3pm", "12 March 2025", "20250312"]);
+
+    let found: Vec<_> = find_dates("締切は H31.04.30 まで").collect();
+    assert_eq!(found[0].datetime, DateTime{year: Some(2019), mon: Some(4), mday: Some(30), ..Default::default()});
+}
+
+#[test]
+pub fn test_find_dates_long_text() {
+    let text: String = (0..500).map(|i| format!("Order {} shipped on Jan {}, 2024 at 10:{:02}. ", i, i % 28 + 1, i % 60)).collect();
+    let found: Vec<_> = find_dates(&text).collect();
+    assert_eq!(found.len(), 500);
+    assert_eq!(found[499].datetime, DateTime{year: Some(2024), mon: Some(1), mday: Some(24), hour: Some(10), min: Some(19), ..Default::default()});
+
+    //# a date across the end of the window the text is read in
+    let date = "Wednesday, January 15th, 2025 at 10:00:00 a.m. EST";
+    for filler in 150..300 {
+        let text = format!("{}{} and more", "ab ".repeat(filler / 3), date);
+        let found: Vec<_> = find_dates(&text).map(|x| text[x.range()].to_owned()).collect();
+        assert_eq!(found, vec![date], "{}", filler);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::date_parser::{iso8601_interval_with_options, iso8601_repeating_interval_with_options, CalendarDuration, ParseOptions};
+use crate::ErrorKind;
+
+fn options() -> ParseOptions {
+    ParseOptions::new().now(crate::utc_time!(2001,11,29, 21,26,35)).zone(chrono_tz::UTC)
+}
+
+#[test]
+pub fn test_iso8601_interval() {
+    let parse = |x| iso8601_interval_with_options(x, &options()).map(|x| (x.start, x.end));
+    assert_eq!(Ok((crate::utc_time!(2024,1,1), crate::utc_time!(2024,2,1))), parse("2024-01-01/2024-02-01"));
+    assert_eq!(Ok((crate::utc_time!(2024,1,1,9,30), crate::utc_time!(2024,1,2,17,0))), parse("2024-01-01T09:30Z/2024-01-02T17:00Z"));
+    assert_eq!(Ok((crate::utc_time!(2024,1,1), crate::utc_time!(2024,2,1))), parse("20240101/20240201"));
+    assert_eq!(Ok((crate::utc_time!(2024,1,29), crate::utc_time!(2024,2,2))), parse("2024-W05-1/2024-W05-5"));
+    //# the end takes the missing upper components from the start
+    assert_eq!(Ok((crate::utc_time!(2024,2,15), crate::utc_time!(2024,2,20))), parse("2024-02-15/20"));
+    assert_eq!(Ok((crate::utc_time!(2008,2,15), crate::utc_time!(2008,3,14))), parse("2008-02-15/03-14"));
+    assert_eq!(Ok((crate::utc_time!(2007,11,13,9,0), crate::utc_time!(2007,11,15,17,0))), parse("2007-11-13T09:00/15T17:00"));
+    assert_eq!(Ok((crate::utc_time!(2007,12,14,13,30), crate::utc_time!(2007,12,14,15,30))), parse("2007-12-14T13:30/15:30"));
+    //# and its zone
+    assert_eq!(Ok((crate::local_time!(2024,1,1, 9,0,0,"+09:00"), crate::local_time!(2024,1,1, 17,0,0,"+09:00"))), parse("2024-01-01T09:00+09:00/T17:00"));
+    assert_eq!(Ok((crate::local_time!(2024,1,1, 9,0,0,"+09:00"), crate::utc_time!(2024,1,1,17,0))), parse("2024-01-01T09:00+09:00/17:00:00Z"));
+}
+
+#[test]
+pub fn test_iso8601_interval_duration() {
+    let parse = |x| iso8601_interval_with_options(x, &options());
+    let month = CalendarDuration { months: 1, ..Default::default() };
+    let interval = parse("2024-01-31/P1M").unwrap();
+    assert_eq!((crate::utc_time!(2024,1,31), crate::utc_time!(2024,2,29), Some(month)), (interval.start, interval.end, interval.duration));
+    let interval = parse("P1M/2024-03-31T12:00Z").unwrap();
+    assert_eq!((crate::utc_time!(2024,2,29,12,0), crate::utc_time!(2024,3,31,12,0), Some(month)), (interval.start, interval.end, interval.duration));
+    let interval = parse("2024-01-01T00:00Z/PT36H").unwrap();
+    assert_eq!((crate::utc_time!(2024,1,1), crate::utc_time!(2024,1,2,12,0)), (interval.start, interval.end));
+    //# completed from now
+    let interval = parse("--12-25/P1D").unwrap();
+    assert_eq!((crate::utc_time!(2001,12,25), crate::utc_time!(2001,12,26)), (interval.start, interval.end));
+}
+
+#[test]
+pub fn test_iso8601_interval_errors() {
+    let parse = |x| iso8601_interval_with_options(x, &options());
+    assert_eq!(ErrorKind::InvalidFormat, parse("2024-01-01").unwrap_err().kind());
+    assert_eq!(ErrorKind::InvalidFormat, parse("P1D/P2D").unwrap_err().kind());
+    assert_eq!(ErrorKind::InvalidFormat, parse("Jan 1 2024/Feb 1 2024").unwrap_err().kind());
+    //# a time alone isn't a start
+    assert_eq!(ErrorKind::InvalidFormat, parse("10:00/12:00").unwrap_err().kind());
+    let err = parse("2024-02-15/30").unwrap_err();
+    assert_eq!((ErrorKind::InvalidDayOfMonth, Some(11..13)), (err.kind(), err.span()));
+    assert_eq!(Some("2024-02-15/30"), err.input());
+    let err = parse("2024-02-15/P1X").unwrap_err();
+    assert_eq!((ErrorKind::InvalidDuration, Some(11..14)), (err.kind(), err.span()));
+}
+
+#[test]
+pub fn test_iso8601_repeating_interval() {
+    let parse = |x| iso8601_repeating_interval_with_options(x, &options()).unwrap();
+    let repeating = parse("R5/2024-01-01T00:00Z/P1D");
+    assert_eq!(Some(5), repeating.repetitions);
+    let starts: Vec<_> = repeating.intervals().map(|x| x.start).collect();
+    assert_eq!(vec![crate::utc_time!(2024,1,1), crate::utc_time!(2024,1,2), crate::utc_time!(2024,1,3), crate::utc_time!(2024,1,4), crate::utc_time!(2024,1,5)], starts);
+    //# months are counted from the first interval
+    let ends: Vec<_> = parse("R3/2024-01-31/P1M").intervals().map(|x| x.end).collect();
+    assert_eq!(vec![crate::utc_time!(2024,2,29), crate::utc_time!(2024,3,31), crate::utc_time!(2024,4,30)], ends);
+    //# as long as the first
+    let ends: Vec<_> = parse("R2/2024-01-01T09:00Z/10:30").intervals().map(|x| x.end).collect();
+    assert_eq!(vec![crate::utc_time!(2024,1,1,10,30), crate::utc_time!(2024,1,1,12,0)], ends);
+    //# back from the end
+    let starts: Vec<_> = parse("R/P1W/2024-03-01T00:00Z").intervals().take(2).map(|x| x.start).collect();
+    assert_eq!(vec![crate::utc_time!(2024,2,23), crate::utc_time!(2024,2,16)], starts);
+    assert_eq!(None, parse("R/2024-01-01/P1D").repetitions);
+    assert_eq!(0, parse("R0/2024-01-01/P1D").intervals().count());
+
+    let parse = |x| iso8601_repeating_interval_with_options(x, &options());
+    assert_eq!(ErrorKind::InvalidFormat, parse("2024-01-01/P1D").unwrap_err().kind());
+    assert_eq!(ErrorKind::InvalidFormat, parse("R٥/2024-01-01/P1D").unwrap_err().kind());
+    let err = parse("R99999999999/2024-01-01/P1D").unwrap_err();
+    assert_eq!((ErrorKind::FieldOutOfRange, Some(1..12)), (err.kind(), err.span()));
+}
@@ -0,0 +1,114 @@
+use proptest::prelude::*;
+
+use crate::date_parser::{date_parse, date_parse_range, date_parse_with_spans, date_strptime, find_dates, iso8601_interval, iso8601_repeating_interval, parse_candidates, parse_date, parse_duration, parse_range, time};
+
+// Pieces of dates, so that the generated strings get past the regexes
+// into the code turning the digits into fields, rather than being
+// rejected as noise right away.
+fn date_like() -> impl Strategy<Value = String> {
+    let piece = prop_oneof![
+        "[0-9]{1,25}",
+        "[-+]?[0-9]{1,12}[-/.:][0-9]{1,12}([-/.:][0-9]{1,12})?",
+        "[0-9]{1,10}(st|nd|rd|th|h|m|s|am|pm)",
+        "(jan|feb|mar|sept|dec|mon|tue|sunday|bc|ad|ce|utc|gmt|est|jst|z|t|w|x)[a-z.]{0,3}",
+        "[HhRrMmSsTt][0-9]{1,12}\\.[0-9]{1,12}\\.[0-9]{1,12}",
+        "[-+][0-9]{1,20}(:[0-9]{1,12})?",
+        "(gmt|utc)?[-+][0-9]{1,12}[.,:][0-9]{1,12}",
+        "[0-9]{2,4}-?[Ww][0-9]{1,3}(-?[0-9]{1,2})?",
+        "'[0-9]{1,12}",
+        "\\[[-+]?[0-9]{1,12}[^\\]]{0,6}\\]",
+        "(America/New_York|Asia/Tokyo|Europe/Lisbon)",
+        "(in )?[0-9]{1,25} (days?|months?|years?|fortnight|hours?) (ago|from now)?",
+        "(next|last|this) (week|weekend|month|year|fri|sun)|today|tomorrow|yesterday|now",
+        "(first|fifth|last|[0-9]{1,3}(st|th)) (day|business day|mon) of (the month|q[0-9]|feb [0-9]{1,12}|[0-9]{1,12})",
+        "(end|start) of (the )?(week|quarter|[0-9]{1,12})",
+        "(now [-+] |in )?-?P([0-9]{1,20}[.,]?[0-9]{0,3}[YMWDTHS]){1,4}",
+        "[-+]?[0-9]{1,20}(\\.[0-9]{1,12})?(h|m|s|d|w|y|mo| days| mons) ?(ago)?",
+        "(R[0-9]{0,12}/)?[0-9]{4}-[0-9]{2}-[0-9]{2}(T[0-9]{2}:[0-9]{2}Z?)?/([0-9]{1,2}-)?[0-9]{1,2}|/P[0-9]{1,20}[DMY]",
+        "(from )?([0-9]{1,12}|mon|jan [0-9]{1,12}|[0-9]{1,2}(am|:[0-9]{2}))( ?[-–] ?| to )([0-9]{1,12}|fri|[0-9]{1,2}(pm|:[0-9]{2}))( march)?",
+        "令和[0-9]{1,4}年|平成|Ｈ[0-9]{1,3}\\.[0-9]{1,3}\\.[0-9]{1,3}",
+        "[-+]?[٠-٩०-९０-９0-9]{1,4}([-:.,][٠-٩०-९０-９0-9]{1,4}){0,2}",
+        "[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}(\\.[٠-٩0-9]{1,3})?([-+][٠-٩0-9]{2}(:?[٠-٩0-9]{2})?|Z)",
+        "[ ,T]",
+    ];
+    prop::collection::vec(piece, 0..8).prop_map(|pieces| pieces.join(" "))
+}
+
+fn parse_all(input: &str) {
+    let _ = date_parse(input, true);
+    let _ = date_parse(input, false);
+    let _ = date_parse_with_spans(input, true);
+    let _ = parse_candidates(input);
+    let _ = find_dates(input).count();
+    let _ = parse_date(input);
+    let _ = parse_duration(input);
+    let _ = date_parse_range(input, true);
+    let _ = parse_range(input);
+    let _ = iso8601_interval(input);
+    let _ = iso8601_repeating_interval(input).map(|x| x.intervals().take(3).count());
+    let _ = time::parse(input);
+    let _ = time::iso8601(input);
+    let _ = time::rfc3339(input);
+    let _ = time::xmlschema(input);
+    let _ = time::rfc2822(input);
+    let _ = time::httpdate(input);
+    let _ = time::jisx0301(input);
+    let _ = time::rfc9557(input);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(2000))]
+
+    #[test]
+    fn test_no_panic_on_any_string(input in "\\PC{0,64}") {
+        parse_all(&input);
+    }
+
+    #[test]
+    fn test_no_panic_on_date_like_strings(input in date_like()) {
+        parse_all(&input);
+    }
+
+    #[test]
+    fn test_no_panic_on_strptime(input in date_like(), format in "(%[-_0^#]?[0-9]{0,3}[a-zA-Z+%]|[ :/-]){0,6}") {
+        let _ = date_strptime(&input, &format);
+        let _ = time::strptime(&input, &format);
+    }
+}
+
+#[test]
+pub fn test_no_panic_on_long_numbers() {
+    let digits = "9".repeat(40);
+    for input in [
+        format!("{}-01-01", digits),
+        format!("2001-{}-01", digits),
+        format!("Jan {} {}", digits, digits),
+        format!("{}:{}:{}", digits, digits, digits),
+        format!("H{}.01.01", digits),
+        format!("{}w{}", digits, digits),
+        format!("2001-01-01 +{}", digits),
+        // fits in i64, but not once in seconds
+        "10:00 +3000000000000000.0h".to_owned(),
+        // a year at the end of the range of i32, taken back across Dec 31
+        "h2148000000.3.0 0am - fri march".to_owned(),
+        format!("{}th", digits),
+        digits.clone(),
+    ] {
+        parse_all(&input);
+    }
+}
+
+#[test]
+pub fn test_no_panic_on_non_ascii_digits() {
+    for input in [
+        "2024-01-02T10:00:00+٠٩",
+        "2024-01-02T10:00:00+٠٩:00",
+        "2024-01-02T10:00:00.٥Z",
+        "٢٠٢٤-01-02T10:00:00Z",
+        "2024-01-02 10:00 +٠٩٠٠",
+        "Tue, 02 Jan 2024 10:00:00 +٠٩٠٠",
+        "P٠٠٠١-02-10",
+    ] {
+        parse_all(input);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::date_parser::{date_parse, DateTime};
+use crate::date_parser::{date_parse, date_parse_with_options, date_parse_with_year_pivot, DateOrder, DateTime, ParseOptions, YearPivot};
 
 #[test]
 pub fn test_ctime_asctime() {
@@ -131,6 +131,10 @@ pub fn test_jis_x0301() {
     assert_eq!(date_parse("H11.05.23T23:55:21Z",false), DateTime{year: Some(1999), mon: Some(5), mday:Some(23), hour: Some(23), min: Some(55), sec: Some(21), zone: Some("Z".to_owned()), offset: Some(0), wday: None, ..Default::default()});
     assert_eq!(date_parse("H31.04.30 23:55:21Z",false), DateTime{year: Some(2019), mon: Some(4), mday:Some(30), hour: Some(23), min: Some(55), sec: Some(21), zone: Some("Z".to_owned()), offset: Some(0), wday: None, ..Default::default()});
     assert_eq!(date_parse("H31.04.30T23:55:21Z",false), DateTime{year: Some(2019), mon: Some(4), mday:Some(30), hour: Some(23), min: Some(55), sec: Some(21), zone: Some("Z".to_owned()), offset: Some(0), wday: None, ..Default::default()});
+    assert_eq!(date_parse("R06.10.18",false), DateTime{year: Some(2024), mon: Some(10), mday:Some(18), ..Default::default()});
+    assert_eq!(date_parse("Ｒ06.10.18",false), DateTime{year: Some(2024), mon: Some(10), mday:Some(18), ..Default::default()});
+    assert_eq!(date_parse("令和6.10.18 09:30",false), DateTime{year: Some(2024), mon: Some(10), mday:Some(18), hour: Some(9), min: Some(30), ..Default::default()});
+    assert_eq!(date_parse("平成31.4.30",false), DateTime{year: Some(2019), mon: Some(4), mday:Some(30), ..Default::default()});
 }
 
 #[test]
@@ -154,6 +158,7 @@ pub fn test_ofx_date() {
 }
 
 #[test]
+#[allow(clippy::octal_escapes)]
 pub fn test_rfc_2822() {
 
     //# rfc 2822
@@ -634,3 +639,86 @@ pub fn test_parse_2(){
     h = date_parse("", true);
     assert_eq!(DateTime::default(), h);
 }
+
+#[test]
+pub fn test_iana_zone() {
+    assert_eq!(date_parse("2024-06-01 10:00 Europe/Berlin",false), DateTime{year: Some(2024), mon: Some(6), mday: Some(1), hour: Some(10), min: Some(0), zone: Some("Europe/Berlin".to_owned()), offset: Some(2*3600), ..Default::default()});
+    assert_eq!(date_parse("2024-01-01 10:00 Europe/Berlin",false), DateTime{year: Some(2024), mon: Some(1), mday: Some(1), hour: Some(10), min: Some(0), zone: Some("Europe/Berlin".to_owned()), offset: Some(3600), ..Default::default()});
+    assert_eq!(date_parse("2024-06-01T10:00[America/Sao_Paulo]",false), DateTime{year: Some(2024), mon: Some(6), mday: Some(1), hour: Some(10), min: Some(0), zone: Some("America/Sao_Paulo".to_owned()), offset: Some(-3*3600), ..Default::default()});
+    assert_eq!(date_parse("2024-06-01 10:00 America/Sao_Paulo",false), DateTime{year: Some(2024), mon: Some(6), mday: Some(1), hour: Some(10), min: Some(0), zone: Some("America/Sao_Paulo".to_owned()), offset: Some(-3*3600), ..Default::default()});
+    assert_eq!(date_parse("20240601T1000[America/New_York]",false), DateTime{year: Some(2024), mon: Some(6), mday: Some(1), hour: Some(10), min: Some(0), zone: Some("America/New_York".to_owned()), offset: Some(-4*3600), ..Default::default()});
+    assert_eq!(date_parse("Sat Jun 1 10:00:00 America/Argentina/Buenos_Aires 2024",false), DateTime{wday: Some(6), year: Some(2024), mon: Some(6), mday: Some(1), hour: Some(10), min: Some(0), sec: Some(0), zone: Some("America/Argentina/Buenos_Aires".to_owned()), offset: Some(-3*3600), ..Default::default()});
+    //# names that look like weekdays or months are not taken for them
+    assert_eq!(date_parse("2024-06-01 10:00 America/Montevideo",false), DateTime{year: Some(2024), mon: Some(6), mday: Some(1), hour: Some(10), min: Some(0), zone: Some("America/Montevideo".to_owned()), offset: Some(-3*3600), ..Default::default()});
+    assert_eq!(date_parse("2024-06-01 10:00 Etc/GMT+5",false), DateTime{year: Some(2024), mon: Some(6), mday: Some(1), hour: Some(10), min: Some(0), zone: Some("Etc/GMT+5".to_owned()), offset: Some(-5*3600), ..Default::default()});
+    //# the offset needs the full date
+    assert_eq!(date_parse("10:00 Europe/Berlin",false), DateTime{hour: Some(10), min: Some(0), zone: Some("Europe/Berlin".to_owned()), ..Default::default()});
+    //# DST gap and overlap
+    assert_eq!(date_parse("2024-03-10 02:30 America/New_York",false), DateTime{year: Some(2024), mon: Some(3), mday: Some(10), hour: Some(2), min: Some(30), zone: Some("America/New_York".to_owned()), offset: Some(-5*3600), ..Default::default()});
+    assert_eq!(date_parse("2024-11-03 01:30 America/New_York",false), DateTime{year: Some(2024), mon: Some(11), mday: Some(3), hour: Some(1), min: Some(30), zone: Some("America/New_York".to_owned()), offset: Some(-4*3600), ..Default::default()});
+    //# unknown names are left alone
+    assert_eq!(date_parse("2024-06-01 10:00 Mars/Olympus",false).zone, Some("Mars".to_owned()));
+}
+
+#[test]
+pub fn test_year_pivot() {
+    assert_eq!(YearPivot::default().complete(69), 1969);
+    assert_eq!(YearPivot::default().complete(68), 2068);
+    assert_eq!(YearPivot::Fixed(1950).complete(50), 1950);
+    assert_eq!(YearPivot::Fixed(1950).complete(49), 2049);
+    assert_eq!(YearPivot::Fixed(1950).complete(0), 2000);
+    assert_eq!(YearPivot::Sliding{reference: 2024, years_before: 80}.complete(44), 1944);
+    assert_eq!(YearPivot::Sliding{reference: 2024, years_before: 80}.complete(43), 2043);
+    assert_eq!(YearPivot::Sliding{reference: 2024, years_before: 80}.complete(24), 2024);
+    assert_eq!(YearPivot::Sliding{reference: 2024, years_before: 0}.complete(23), 2123);
+    assert_eq!(YearPivot::ClosestTo(2024).complete(74), 1974);
+    assert_eq!(YearPivot::ClosestTo(2024).complete(73), 2073);
+    assert_eq!(YearPivot::ClosestTo(2024).complete(99), 1999);
+    assert_eq!(YearPivot::ClosestTo(2024).complete(100), 100);
+    assert_eq!(YearPivot::ClosestTo(2024).complete(-1), -1);
+
+    assert_eq!(date_parse_with_year_pivot("01-02-03", YearPivot::Fixed(1950)), DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()});
+    assert_eq!(date_parse_with_year_pivot("51-02-03", YearPivot::Fixed(1950)), DateTime{year: Some(1951), mon: Some(2), mday: Some(3), ..Default::default()});
+    assert_eq!(date_parse_with_year_pivot("Dec 31 75", YearPivot::ClosestTo(2030)), DateTime{year: Some(2075), mon: Some(12), mday: Some(31), ..Default::default()});
+    assert_eq!(date_parse_with_year_pivot("Sat Aug 28 02:55:50 '99", YearPivot::ClosestTo(2130)), DateTime{wday: Some(6), year: Some(2099), mon: Some(8), mday: Some(28), hour: Some(2), min: Some(55), sec: Some(50), ..Default::default()});
+    //# years written with a sign or more digits are not completed
+    assert_eq!(date_parse_with_year_pivot("0051-02-03", YearPivot::Fixed(1950)), DateTime{year: Some(51), mon: Some(2), mday: Some(3), ..Default::default()});
+    //# cwyear is completed the same way
+    assert_eq!(date_parse_with_year_pivot("51-w10-3", YearPivot::Fixed(1950)), DateTime{cwyear: Some(1951), cweek: Some(10), cwday: Some(3), ..Default::default()});
+    assert_eq!(date_parse_with_year_pivot("49-w10-3", YearPivot::Fixed(1950)), DateTime{cwyear: Some(2049), cweek: Some(10), cwday: Some(3), ..Default::default()});
+}
+
+#[test]
+pub fn test_date_order() {
+    let mdy = ParseOptions::new().date_order(DateOrder::Mdy);
+    let dmy = ParseOptions::new().date_order(DateOrder::Dmy);
+    let ymd = ParseOptions::new().date_order(DateOrder::Ymd);
+
+    //# Ruby's own reading
+    assert_eq!(date_parse("03/04/2024",true), DateTime{year: Some(2024), mon: Some(4), mday: Some(3), ..Default::default()});
+    assert_eq!(date_parse("03/04/24",true), DateTime{year: Some(2003), mon: Some(4), mday: Some(24), ..Default::default()});
+    assert_eq!(date_parse_with_options("03/04/2024", &ParseOptions::new()), DateTime{year: Some(2024), mon: Some(4), mday: Some(3), ..Default::default()});
+
+    assert_eq!(date_parse_with_options("03/04/2024", &mdy), DateTime{year: Some(2024), mon: Some(3), mday: Some(4), ..Default::default()});
+    assert_eq!(date_parse_with_options("03.04.2024", &mdy), DateTime{year: Some(2024), mon: Some(3), mday: Some(4), ..Default::default()});
+    assert_eq!(date_parse_with_options("03/04/24", &mdy), DateTime{year: Some(2024), mon: Some(3), mday: Some(4), ..Default::default()});
+    assert_eq!(date_parse_with_options("3/4", &mdy), DateTime{mon: Some(3), mday: Some(4), ..Default::default()});
+    assert_eq!(date_parse_with_options("12/31/'99", &mdy), DateTime{year: Some(1999), mon: Some(12), mday: Some(31), ..Default::default()});
+
+    assert_eq!(date_parse_with_options("03/04/2024", &dmy), DateTime{year: Some(2024), mon: Some(4), mday: Some(3), ..Default::default()});
+    assert_eq!(date_parse_with_options("03.04.2024", &dmy), DateTime{year: Some(2024), mon: Some(4), mday: Some(3), ..Default::default()});
+    assert_eq!(date_parse_with_options("03/04/24", &dmy), DateTime{year: Some(2024), mon: Some(4), mday: Some(3), ..Default::default()});
+    assert_eq!(date_parse_with_options("3/4", &dmy), DateTime{mon: Some(4), mday: Some(3), ..Default::default()});
+    assert_eq!(date_parse_with_options("Sat 03/04/2024 10:00", &dmy), DateTime{wday: Some(6), year: Some(2024), mon: Some(4), mday: Some(3), hour: Some(10), min: Some(0), ..Default::default()});
+
+    assert_eq!(date_parse_with_options("03/04/05", &ymd), DateTime{year: Some(2003), mon: Some(4), mday: Some(5), ..Default::default()});
+    assert_eq!(date_parse_with_options("3/4", &ymd), DateTime{mon: Some(3), mday: Some(4), ..Default::default()});
+    assert_eq!(date_parse_with_options("03/04/2024", &ymd), DateTime{year: Some(2024), mon: Some(4), mday: Some(3), ..Default::default()});
+
+    //# a leading year always reads year, month, day
+    assert_eq!(date_parse_with_options("2024/03/04", &dmy), DateTime{year: Some(2024), mon: Some(3), mday: Some(4), ..Default::default()});
+    assert_eq!(date_parse_with_options("2024.03.04", &mdy), DateTime{year: Some(2024), mon: Some(3), mday: Some(4), ..Default::default()});
+
+    //# out of range fields are kept for the caller to reject, not swapped
+    assert_eq!(date_parse_with_options("13/04/2024", &mdy), DateTime{year: Some(2024), mon: Some(13), mday: Some(4), ..Default::default()});
+}
@@ -0,0 +1,68 @@
+use crate::date_parser::{date_parse_range, parse_range_with_options, DateTime, ParseOptions};
+use crate::ErrorKind;
+
+#[test]
+pub fn test_date_parse_range() {
+    let jan3 = DateTime { year: Some(2024), mon: Some(1), mday: Some(3), ..Default::default() };
+    let jan7 = DateTime { year: Some(2024), mon: Some(1), mday: Some(7), ..Default::default() };
+    assert_eq!(Some((jan3.clone(), jan7)), date_parse_range("Jan 3–7, 2024", true));
+    let mar3 = DateTime { mon: Some(3), mday: Some(3), ..Default::default() };
+    let mar5 = DateTime { mon: Some(3), mday: Some(5), ..Default::default() };
+    assert_eq!(Some((mar3.clone(), mar5.clone())), date_parse_range("3-5 March", true));
+    assert_eq!(Some((mar3, mar5)), date_parse_range("3rd to 5th of March", true));
+    let y2023 = DateTime { year: Some(2023), ..Default::default() };
+    let y2024 = DateTime { year: Some(2024), ..Default::default() };
+    assert_eq!(Some((y2023, y2024)), date_parse_range("2023-2024", true));
+    let jan1 = DateTime { year: Some(2024), mon: Some(1), mday: Some(1), ..Default::default() };
+    let feb1 = DateTime { year: Some(2024), mon: Some(2), mday: Some(1), ..Default::default() };
+    assert_eq!(Some((jan1, feb1)), date_parse_range("2024-01-01 - 2024-02-01", true));
+    //# the year written once
+    let feb5 = DateTime { year: Some(2024), mon: Some(2), mday: Some(5), ..Default::default() };
+    assert_eq!(Some((jan3, feb5)), date_parse_range("Jan 3 – Feb 5, 2024", true));
+    let dec28 = DateTime { year: Some(2024), mon: Some(12), mday: Some(28), ..Default::default() };
+    let jan3 = DateTime { year: Some(2025), mon: Some(1), mday: Some(3), ..Default::default() };
+    assert_eq!(Some((dec28, jan3)), date_parse_range("Dec 28 through Jan 3, 2025", true));
+
+    let (start, end) = date_parse_range("Mon–Fri 9am–5pm", true).unwrap();
+    assert_eq!((Some(1), Some(9), None), (start.wday, start.hour, start.min));
+    assert_eq!((Some(5), Some(17), None), (end.wday, end.hour, end.min));
+    let (start, end) = date_parse_range("from 10:00 to 14:30 CET", true).unwrap();
+    assert_eq!((Some(10), Some(0), Some("CET"), Some(3600)), (start.hour, start.min, start.zone.as_deref(), start.offset));
+    assert_eq!((Some(14), Some(30), Some("CET"), Some(3600)), (end.hour, end.min, end.zone.as_deref(), end.offset));
+    //# the date of an end that is a time alone
+    let (start, end) = date_parse_range("Sat Aug 28 2021 10:00 - 14:00", true).unwrap();
+    assert_eq!((Some(2021), Some(8), Some(28), Some(10)), (start.year, start.mon, start.mday, start.hour));
+    assert_eq!((Some(2021), Some(8), Some(28), Some(14)), (end.year, end.mon, end.mday, end.hour));
+
+    assert_eq!(None, date_parse_range("Jan 3, 2024", true));
+    assert_eq!(None, date_parse_range("2024-01-03", true));
+    assert_eq!(None, date_parse_range("pages 3 - 5", true));
+}
+
+#[test]
+pub fn test_parse_range() {
+    // a Thursday
+    let options = ParseOptions::new().now(crate::utc_time!(2001,11,29, 21,26,35)).zone(chrono_tz::UTC);
+    let parse = |x| parse_range_with_options(x, &options).map(|x| (x.start, x.end));
+    //# an end without a time runs to the start of the day after it
+    assert_eq!(Ok((crate::utc_time!(2024,1,3), crate::utc_time!(2024,1,8))), parse("Jan 3–7, 2024"));
+    assert_eq!(Ok((crate::utc_time!(2001,3,3), crate::utc_time!(2001,3,6))), parse("3-5 March"));
+    assert_eq!(Ok((crate::utc_time!(2024,12,28), crate::utc_time!(2025,1,4))), parse("Dec 28 - Jan 3, 2025"));
+    assert_eq!(Ok((crate::utc_time!(2024,2,27), crate::utc_time!(2024,3,1))), parse("Feb 27–29, 2024"));
+    assert_eq!(Ok((crate::utc_time!(2001,11,26), crate::utc_time!(2001,12,1))), parse("Mon–Fri"));
+    //# of the month after it
+    assert_eq!(Ok((crate::utc_time!(2024,1,1), crate::utc_time!(2024,4,1))), parse("Jan – Mar 2024"));
+    //# of the year after it
+    assert_eq!(Ok((crate::utc_time!(2023,1,1), crate::utc_time!(2025,1,1))), parse("2023-2024"));
+    //# and one with a time ends at that time
+    assert_eq!(Ok((crate::utc_time!(2001,11,26,9), crate::utc_time!(2001,11,30,17))), parse("Mon–Fri 9am–5pm"));
+    assert_eq!(Ok((crate::utc_time!(2001,11,29,15), crate::utc_time!(2001,11,29,19,30))), parse("from 10:00 to 14:30 EST"));
+    assert_eq!(Ok((crate::utc_time!(2021,8,28,10), crate::utc_time!(2021,8,28,14))), parse("Sat Aug 28 2021 10:00 - 14:00"));
+    //# in the zone of the range, through a change of offset
+    let options = ParseOptions::new().now(crate::utc_time!(2001,11,29, 21,26,35)).zone(chrono_tz::America::New_York);
+    let interval = parse_range_with_options("Mar 9–10, 2024", &options).unwrap();
+    assert_eq!(crate::local_time!(2024,3,11,0,0,0,"-04:00"), interval.end);
+
+    assert_eq!(ErrorKind::NoDateFound, parse("Jan 3, 2024").unwrap_err().kind());
+    assert_eq!(ErrorKind::InvalidDayOfMonth, parse("Feb 27–30, 2024").unwrap_err().kind());
+}
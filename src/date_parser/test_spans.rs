@@ -0,0 +1,45 @@
+use crate::date_parser::{date_parse, date_parse_with_spans, FieldSpan};
+
+fn span<'a>(spans: &'a [FieldSpan], field: &str) -> &'a FieldSpan {
+    spans.iter().find(|x| x.field == field).unwrap()
+}
+
+#[test]
+pub fn test_spans() {
+    let input = "Sat Aug 28 02:55:50 +09:00 2021";
+    let (datetime, spans) = date_parse_with_spans(input, false);
+    assert_eq!(datetime, date_parse(input, false));
+    assert_eq!(span(&spans, "wday"), &FieldSpan{field: "wday", start: 0, end: 3, parser: "parse_day"});
+    assert_eq!(&input[span(&spans, "hour").range()], "02:55:50 +09:00");
+    assert_eq!(span(&spans, "hour").parser, "parse_time");
+    assert_eq!(span(&spans, "offset").range(), span(&spans, "zone").range());
+    assert_eq!(span(&spans, "mon").parser, "parse_us");
+    assert_eq!(&input[span(&spans, "mday").range()], "Aug 28 02:55:50 +09:00 2021");
+    assert_eq!(spans.iter().map(|x| x.field).collect::<Vec<_>>(), vec!["wday", "hour", "min", "sec", "zone", "year", "mon", "mday", "offset"]);
+}
+
+#[test]
+pub fn test_spans_in_text() {
+    let input = "[worker-3] job finished at 2024-10-18T09:30:00Z, took 3s";
+    let (_, spans) = date_parse_with_spans(input, false);
+    assert_eq!(&input[span(&spans, "year").range()], "2024-10-18");
+    assert_eq!(span(&spans, "year").parser, "parse_iso");
+    assert_eq!(&input[span(&spans, "hour").range()], "09:30:00Z");
+
+    //# multibyte characters before the date
+    let input = "締切： H31.04.30 まで";
+    let (datetime, spans) = date_parse_with_spans(input, false);
+    assert_eq!(datetime.year, Some(2019));
+    assert_eq!(&input[span(&spans, "year").range()], "H31.04.30");
+    assert_eq!(span(&spans, "year").parser, "parse_jis");
+}
+
+#[test]
+pub fn test_spans_zone_name() {
+    let input = "2024-03-10 02:30 America/New_York";
+    let (datetime, spans) = date_parse_with_spans(input, false);
+    assert!(datetime.offset.is_some());
+    assert_eq!(&input[span(&spans, "zone").range()], "America/New_York");
+    assert_eq!(span(&spans, "offset").parser, "parse_iana_zone");
+    assert_eq!(&input[span(&spans, "mon").range()], "2024-03-10");
+}
@@ -0,0 +1,220 @@
+use crate::date_parser::{httpdate, iso8601, jisx0301, rfc2822, rfc3339, rfc9557, xmlschema, DateTime, Ixdtf};
+
+#[test]
+pub fn test_iso8601_ext_datetime() {
+    assert_eq!(iso8601("1999-05-23"), Some(DateTime{year: Some(1999), mon: Some(5), mday: Some(23), ..Default::default()}));
+    assert_eq!(iso8601("1999-05-23T23:55:21"), Some(DateTime{year: Some(1999), mon: Some(5), mday: Some(23), hour: Some(23), min: Some(55), sec: Some(21), ..Default::default()}));
+    assert_eq!(iso8601("1999-05-23t23:55:21.123+09:00"), Some(DateTime{year: Some(1999), mon: Some(5), mday: Some(23), hour: Some(23), min: Some(55), sec: Some(21), sec_fraction: Some(0.123), zone: Some("+09:00".to_owned()), offset: Some(9*3600), ..Default::default()}));
+    assert_eq!(iso8601("1999-05-23T23:55Z"), Some(DateTime{year: Some(1999), mon: Some(5), mday: Some(23), hour: Some(23), min: Some(55), zone: Some("Z".to_owned()), offset: Some(0), ..Default::default()}));
+    assert_eq!(iso8601("-1999-05-23"), Some(DateTime{year: Some(-1999), mon: Some(5), mday: Some(23), ..Default::default()}));
+    assert_eq!(iso8601("+011985-04-12"), Some(DateTime{year: Some(11985), mon: Some(4), mday: Some(12), ..Default::default()}));
+    assert_eq!(iso8601("99-05-23"), Some(DateTime{year: Some(1999), mon: Some(5), mday: Some(23), ..Default::default()}));
+    assert_eq!(iso8601("1999-05"), Some(DateTime{year: Some(1999), mon: Some(5), ..Default::default()}));
+    assert_eq!(iso8601("--05-23"), Some(DateTime{mon: Some(5), mday: Some(23), ..Default::default()}));
+    assert_eq!(iso8601("---23"), Some(DateTime{mday: Some(23), ..Default::default()}));
+
+    assert_eq!(iso8601("1999-143"), Some(DateTime{year: Some(1999), yday: Some(143), ..Default::default()}));
+    assert_eq!(iso8601("-143"), Some(DateTime{yday: Some(143), ..Default::default()}));
+
+    assert_eq!(iso8601("1999-W20-7"), Some(DateTime{cwyear: Some(1999), cweek: Some(20), cwday: Some(7), ..Default::default()}));
+    assert_eq!(iso8601("-W20-7"), Some(DateTime{cweek: Some(20), cwday: Some(7), ..Default::default()}));
+    assert_eq!(iso8601("-w-7"), Some(DateTime{cwday: Some(7), ..Default::default()}));
+
+    //# digits are ASCII only
+    assert_eq!(iso8601("٢٠٢٤-01-02"), None);
+    assert_eq!(iso8601("2024-01-02T10:00:00.٥Z"), None);
+    assert_eq!(iso8601("2024-01-02T10:00:00+٠٩"), None);
+    assert_eq!(iso8601("２０２４０１０２"), None);
+}
+
+#[test]
+pub fn test_iso8601_bas_datetime() {
+    assert_eq!(iso8601("19990523"), Some(DateTime{year: Some(1999), mon: Some(5), mday: Some(23), ..Default::default()}));
+    assert_eq!(iso8601("990523T235521"), Some(DateTime{year: Some(1999), mon: Some(5), mday: Some(23), hour: Some(23), min: Some(55), sec: Some(21), ..Default::default()}));
+    assert_eq!(iso8601("19990523T235521,5-0900"), Some(DateTime{year: Some(1999), mon: Some(5), mday: Some(23), hour: Some(23), min: Some(55), sec: Some(21), sec_fraction: Some(0.5), zone: Some("-0900".to_owned()), offset: Some(-9*3600), ..Default::default()}));
+    assert_eq!(iso8601("--0523"), Some(DateTime{mon: Some(5), mday: Some(23), ..Default::default()}));
+    assert_eq!(iso8601("---23"), Some(DateTime{mday: Some(23), ..Default::default()}));
+    assert_eq!(iso8601("1999143"), Some(DateTime{year: Some(1999), yday: Some(143), ..Default::default()}));
+    assert_eq!(iso8601("1999W207"), Some(DateTime{cwyear: Some(1999), cweek: Some(20), cwday: Some(7), ..Default::default()}));
+    assert_eq!(iso8601("-W207"), Some(DateTime{cweek: Some(20), cwday: Some(7), ..Default::default()}));
+}
+
+#[test]
+pub fn test_iso8601_time() {
+    assert_eq!(iso8601("23:55"), Some(DateTime{hour: Some(23), min: Some(55), ..Default::default()}));
+    assert_eq!(iso8601("23:55:21.5Z"), Some(DateTime{hour: Some(23), min: Some(55), sec: Some(21), sec_fraction: Some(0.5), zone: Some("Z".to_owned()), offset: Some(0), ..Default::default()}));
+    assert_eq!(iso8601("2355"), Some(DateTime{hour: Some(23), min: Some(55), ..Default::default()}));
+    assert_eq!(iso8601("235521+0900"), Some(DateTime{hour: Some(23), min: Some(55), sec: Some(21), zone: Some("+0900".to_owned()), offset: Some(9*3600), ..Default::default()}));
+}
+
+#[test]
+pub fn test_iso8601_invalid() {
+    assert_eq!(iso8601(""), None);
+    assert_eq!(iso8601("foo"), None);
+    assert_eq!(iso8601("01/02/03"), None);
+    assert_eq!(iso8601("Sat Aug 28 02:55:50 1999"), None);
+    assert_eq!(iso8601("1999-05-23 23:55:21"), None);
+    assert_eq!(iso8601("1999-05-23T23:55:21 JST"), None);
+    assert_eq!(iso8601("garbage 1999-05-23"), None);
+    assert_eq!(iso8601("1999-05-23T23"), None);
+    assert_eq!(iso8601("99999999999-05-23"), None);
+}
+
+#[test]
+pub fn test_rfc3339() {
+    assert_eq!(rfc3339("2001-02-03T04:05:06+07:00"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("+07:00".to_owned()), offset: Some(7*3600), ..Default::default()}));
+    assert_eq!(rfc3339("2001-02-03 04:05:06.25z"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), sec: Some(6), sec_fraction: Some(0.25), zone: Some("z".to_owned()), offset: Some(0), ..Default::default()}));
+    assert_eq!(rfc3339("-2001-02-03T04:05:06-00:00"), Some(DateTime{year: Some(-2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("-00:00".to_owned()), offset: Some(0), ..Default::default()}));
+
+    assert_eq!(rfc3339("2001-02-03T04:05:06"), None);
+    assert_eq!(rfc3339("2001-02-03T04:05+07:00"), None);
+    assert_eq!(rfc3339("2001-02-03T04:05:06,25+07:00"), None);
+    assert_eq!(rfc3339("2001-02-03T04:05:06.٥Z"), None);
+    assert_eq!(rfc3339("2001-02-03T04:05:06+٠٧:00"), None);
+    assert_eq!(rfc3339("2001-02-03T04:05:06+0700"), None);
+    assert_eq!(rfc3339("2001-02-03"), None);
+    assert_eq!(rfc3339("01-02-03T04:05:06Z"), None);
+}
+
+#[test]
+pub fn test_xmlschema() {
+    assert_eq!(xmlschema("2001-02-03T04:05:06.07+08:00"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), sec: Some(6), sec_fraction: Some(0.07), zone: Some("+08:00".to_owned()), offset: Some(8*3600), ..Default::default()}));
+    assert_eq!(xmlschema("2001-02-03"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(xmlschema("2001-02-03Z"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), zone: Some("Z".to_owned()), offset: Some(0), ..Default::default()}));
+    assert_eq!(xmlschema("2001-02"), Some(DateTime{year: Some(2001), mon: Some(2), ..Default::default()}));
+    assert_eq!(xmlschema("-2001"), Some(DateTime{year: Some(-2001), ..Default::default()}));
+    assert_eq!(xmlschema("04:05:06"), Some(DateTime{hour: Some(4), min: Some(5), sec: Some(6), ..Default::default()}));
+    assert_eq!(xmlschema("04:05:06.5-09:00"), Some(DateTime{hour: Some(4), min: Some(5), sec: Some(6), sec_fraction: Some(0.5), zone: Some("-09:00".to_owned()), offset: Some(-9*3600), ..Default::default()}));
+    assert_eq!(xmlschema("--02-03"), Some(DateTime{mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(xmlschema("--02Z"), Some(DateTime{mon: Some(2), zone: Some("Z".to_owned()), offset: Some(0), ..Default::default()}));
+    assert_eq!(xmlschema("---03"), Some(DateTime{mday: Some(3), ..Default::default()}));
+
+    assert_eq!(xmlschema("2001-02-03 04:05:06"), None);
+    assert_eq!(xmlschema("2001-02-03T04:05"), None);
+    assert_eq!(xmlschema("01-02-03"), None);
+    assert_eq!(xmlschema("04:05"), None);
+    assert_eq!(xmlschema("2001-02-03T04:05:06+0900"), None);
+    assert_eq!(xmlschema("2001-02-03T04:05:06.٥Z"), None);
+    assert_eq!(xmlschema("--٠٢-03"), None);
+}
+
+#[test]
+pub fn test_rfc2822() {
+    assert_eq!(rfc2822("Sat, 3 Feb 2001 04:05:06 +0700"), Some(DateTime{wday: Some(6), mday: Some(3), mon: Some(2), year: Some(2001), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("+0700".to_owned()), offset: Some(7*3600), ..Default::default()}));
+    assert_eq!(rfc2822("3 Feb 2001 04:05 UT"), Some(DateTime{mday: Some(3), mon: Some(2), year: Some(2001), hour: Some(4), min: Some(5), zone: Some("UT".to_owned()), offset: Some(0), ..Default::default()}));
+    assert_eq!(rfc2822("Sat, 3 Feb 49 04:05:06 EST"), Some(DateTime{wday: Some(6), mday: Some(3), mon: Some(2), year: Some(2049), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("EST".to_owned()), offset: Some(-5*3600), ..Default::default()}));
+    assert_eq!(rfc2822("Sat, 3 Feb 50 04:05:06 EST"), Some(DateTime{wday: Some(6), mday: Some(3), mon: Some(2), year: Some(1950), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("EST".to_owned()), offset: Some(-5*3600), ..Default::default()}));
+    assert_eq!(rfc2822("Sat, 3 Feb 101 04:05:06 Z"), Some(DateTime{wday: Some(6), mday: Some(3), mon: Some(2), year: Some(2001), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("Z".to_owned()), offset: Some(0), ..Default::default()}));
+    assert_eq!(rfc2822("Sat, 3 Feb 2001 04:05:06 a"), Some(DateTime{wday: Some(6), mday: Some(3), mon: Some(2), year: Some(2001), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("a".to_owned()), offset: Some(3600), ..Default::default()}));
+
+    assert_eq!(rfc2822("Sat, 3 Feb 2001 04:05:06"), None);
+    assert_eq!(rfc2822("Sat, 3 Feb 2001 04:05:06 JST"), None);
+    assert_eq!(rfc2822("Sat, 3 Feb 2001 04:05:06 J"), None);
+    assert_eq!(rfc2822("Sat 3 Feb 2001 04:05:06 +0700"), None);
+    assert_eq!(rfc2822("Sat, 3 Feb 2001 04:05:06 +0700 (comment)"), None);
+    assert_eq!(rfc2822("Sat, 3 Feb 2001 04:05:06 +٠٧٠٠"), None);
+}
+
+#[test]
+pub fn test_httpdate() {
+    assert_eq!(httpdate("Sat, 03 Feb 2001 04:05:06 GMT"), Some(DateTime{wday: Some(6), mday: Some(3), mon: Some(2), year: Some(2001), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("GMT".to_owned()), offset: Some(0), ..Default::default()}));
+    assert_eq!(httpdate("Saturday, 03-Feb-01 04:05:06 GMT"), Some(DateTime{wday: Some(6), mday: Some(3), mon: Some(2), year: Some(2001), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("GMT".to_owned()), offset: Some(0), ..Default::default()}));
+    assert_eq!(httpdate("Saturday, 03-Feb-99 04:05:06 GMT"), Some(DateTime{wday: Some(6), mday: Some(3), mon: Some(2), year: Some(1999), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("GMT".to_owned()), offset: Some(0), ..Default::default()}));
+    assert_eq!(httpdate("Sat Feb  3 04:05:06 2001"), Some(DateTime{wday: Some(6), mday: Some(3), mon: Some(2), year: Some(2001), hour: Some(4), min: Some(5), sec: Some(6), ..Default::default()}));
+
+    assert_eq!(httpdate("Sat, 03 Feb 2001 04:05:06 +0000"), None);
+    assert_eq!(httpdate("Sat, 3 Feb 2001 04:05:06 GMT"), None);
+    assert_eq!(httpdate("Sat, 03-Feb-01 04:05:06 GMT"), None);
+    assert_eq!(httpdate("Sat Feb  3 04:05:06 01"), None);
+    assert_eq!(httpdate("Sat, 03 Feb ٢٠٠١ 04:05:06 GMT"), None);
+}
+
+#[test]
+pub fn test_jisx0301() {
+    assert_eq!(jisx0301("H13.02.03"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(jisx0301("R06.10.18"), Some(DateTime{year: Some(2024), mon: Some(10), mday: Some(18), ..Default::default()}));
+    assert_eq!(jisx0301("r06.10.18"), Some(DateTime{year: Some(2024), mon: Some(10), mday: Some(18), ..Default::default()}));
+    assert_eq!(jisx0301("13.02.03"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(jisx0301("H13.02.03T04:05"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), ..Default::default()}));
+    assert_eq!(jisx0301("H13.02.03T04:05:06,07Z"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), sec: Some(6), sec_fraction: Some(0.07), zone: Some("Z".to_owned()), offset: Some(0), ..Default::default()}));
+    assert_eq!(jisx0301("H13.02.03T04:05:06+0700"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("+0700".to_owned()), offset: Some(7*3600), ..Default::default()}));
+    assert_eq!(jisx0301("M06.01.01"), Some(DateTime{year: Some(1873), mon: Some(1), mday: Some(1), ..Default::default()}));
+    assert_eq!(jisx0301("T01.07.30"), Some(DateTime{year: Some(1912), mon: Some(7), mday: Some(30), ..Default::default()}));
+    assert_eq!(jisx0301("S64.01.07"), Some(DateTime{year: Some(1989), mon: Some(1), mday: Some(7), ..Default::default()}));
+    assert_eq!(jisx0301("H31.04.30"), Some(DateTime{year: Some(2019), mon: Some(4), mday: Some(30), ..Default::default()}));
+    assert_eq!(jisx0301("R01.05.01"), Some(DateTime{year: Some(2019), mon: Some(5), mday: Some(1), ..Default::default()}));
+
+    //# full-width initials and kanji era names
+    assert_eq!(jisx0301("Ｒ06.10.18"), Some(DateTime{year: Some(2024), mon: Some(10), mday: Some(18), ..Default::default()}));
+    assert_eq!(jisx0301("令和06.10.18"), Some(DateTime{year: Some(2024), mon: Some(10), mday: Some(18), ..Default::default()}));
+    assert_eq!(jisx0301("平成13.02.03"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(jisx0301("昭和40.05.23"), Some(DateTime{year: Some(1965), mon: Some(5), mday: Some(23), ..Default::default()}));
+    assert_eq!(jisx0301("大正11.05.23"), Some(DateTime{year: Some(1922), mon: Some(5), mday: Some(23), ..Default::default()}));
+    assert_eq!(jisx0301("明治11.05.23"), Some(DateTime{year: Some(1878), mon: Some(5), mday: Some(23), ..Default::default()}));
+
+    //# outside of the era
+    assert_eq!(jisx0301("H31.05.01"), None);
+    assert_eq!(jisx0301("H32.01.01"), None);
+    assert_eq!(jisx0301("R01.04.30"), None);
+    assert_eq!(jisx0301("S64.01.08"), None);
+    assert_eq!(jisx0301("H01.01.07"), None);
+    assert_eq!(jisx0301("T15.12.25"), None);
+    assert_eq!(jisx0301("M45.07.30"), None);
+    assert_eq!(jisx0301("M05.12.31"), None);
+    assert_eq!(jisx0301("H00.01.01"), None);
+    assert_eq!(jisx0301("H13.02.30"), None);
+
+    //# two digits each
+    assert_eq!(jisx0301("R1.5.1"), None);
+    assert_eq!(jisx0301("H１３.02.03"), None);
+    assert_eq!(jisx0301("令和6.10.18"), None);
+
+    //# falls back to iso8601
+    assert_eq!(jisx0301("2001-02-03"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(jisx0301("X13.02.03"), None);
+}
+
+#[test]
+pub fn test_rfc9557() {
+    assert_eq!(rfc9557("2001-02-03T04:05:06+01:00"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("+01:00".to_owned()), offset: Some(3600), ..Default::default()}));
+    assert_eq!(rfc9557("2001-02-03T04:05:06+01:00[Europe/Paris]"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("+01:00".to_owned()), offset: Some(3600), ixdtf: Some(Ixdtf{zone: Some("Europe/Paris".to_owned()), ..Default::default()}), ..Default::default()}));
+    assert_eq!(rfc9557("2001-02-03T04:05:06+01:00[!Europe/Paris][u-ca=gregory]"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), sec: Some(6), zone: Some("+01:00".to_owned()), offset: Some(3600), ixdtf: Some(Ixdtf{zone: Some("Europe/Paris".to_owned()), zone_critical: true, calendar: Some("gregory".to_owned()), calendar_critical: false}), ..Default::default()}));
+    assert_eq!(rfc9557("2024-10-18T09:30:00.5+09:00[u-ca=japanese]"), Some(DateTime{year: Some(2024), mon: Some(10), mday: Some(18), hour: Some(9), min: Some(30), sec: Some(0), sec_fraction: Some(0.5), zone: Some("+09:00".to_owned()), offset: Some(9*3600), ixdtf: Some(Ixdtf{calendar: Some("japanese".to_owned()), ..Default::default()}), ..Default::default()}));
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[!u-ca=japanese]").and_then(|x| x.ixdtf), Some(Ixdtf{calendar: Some("japanese".to_owned()), calendar_critical: true, ..Default::default()}));
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[+09:00]").and_then(|x| x.ixdtf), Some(Ixdtf{zone: Some("+09:00".to_owned()), ..Default::default()}));
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[!+09:00]").and_then(|x| x.ixdtf), Some(Ixdtf{zone: Some("+09:00".to_owned()), zone_critical: true, ..Default::default()}));
+    assert_eq!(rfc9557("2001-02-03T04:05:06+01:00[u-ca=GREGORY]").and_then(|x| x.ixdtf), Some(Ixdtf{calendar: Some("GREGORY".to_owned()), ..Default::default()}));
+    assert!(rfc9557("2001-02-03T04:05:06+01:00[foo=averylongvalue-Mixed9]").is_some());
+
+    //# the critical zone has to agree with the offset at that instant
+    assert!(rfc9557("2024-07-01T10:00:00-04:00[!America/New_York]").is_some());
+    assert!(rfc9557("2024-01-01T10:00:00-05:00[!America/New_York]").is_some());
+    assert_eq!(rfc9557("2024-07-01T10:00:00-05:00[!America/New_York]"), None);
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[!+08:00]"), None);
+    //# an elective zone that disagrees is kept
+    assert_eq!(rfc9557("2024-07-01T10:00:00-05:00[America/New_York]").map(|x| x.offset), Some(Some(-5*3600)));
+    //# Z and -00:00 leave the local offset open
+    assert!(rfc9557("2024-07-01T10:00:00Z[!America/New_York]").is_some());
+    assert!(rfc9557("2024-07-01T10:00:00-00:00[!America/New_York]").is_some());
+    //# but the zone must still be known
+    assert_eq!(rfc9557("2024-01-01T00:00:00Z[!Not/AZone]"), None);
+    assert_eq!(rfc9557("2024-01-01T00:00:00-00:00[!Not/AZone]"), None);
+
+    //# unknown suffixes
+    assert!(rfc9557("2024-10-18T09:30:00+09:00[foo=bar]").is_some());
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[!foo=bar]"), None);
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[!Mars/Olympus]"), None);
+    //# conflicting calendars
+    assert!(rfc9557("2024-10-18T09:30:00+09:00[u-ca=japanese][u-ca=gregory]").is_some());
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[u-ca=japanese][!u-ca=gregory]"), None);
+
+    //# malformed
+    assert_eq!(rfc9557("2024-10-18T09:30:00[Asia/Tokyo]"), None);
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00 [Asia/Tokyo]"), None);
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[u-ca=japanese][Asia/Tokyo]"), None);
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[Asia/Tokyo][Asia/Tokyo]"), None);
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[Asia/Tokyo]x"), None);
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[Asia/Tokyo"), None);
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[U-CA=japanese]"), None);
+    assert_eq!(rfc9557("2024-10-18T09:30:00+09:00[+٠٩:00]"), None);
+}
@@ -0,0 +1,79 @@
+use crate::date_parser::{date_strptime, date_strptime2, DateTime};
+
+#[test]
+pub fn test_strptime_ymd() {
+    assert_eq!(date_strptime("2001-02-03", "%Y-%m-%d"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(date_strptime("2001-02-03", "%F"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(date_strptime("02/03/01", "%D"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(date_strptime("02/03/99", "%x"), Some(DateTime{year: Some(1999), mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(date_strptime("20010203", "%Y%m%d"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(date_strptime("-2001-02-03", "%Y-%m-%d"), Some(DateTime{year: Some(-2001), mon: Some(2), mday: Some(3), ..Default::default()}));
+    assert_eq!(date_strptime("2001", "%C%y"), Some(DateTime{year: Some(2001), ..Default::default()}));
+    assert_eq!(date_strptime("20", "%C"), Some(DateTime::default()));
+    assert_eq!(date_strptime("2001-034", "%Y-%j"), Some(DateTime{year: Some(2001), yday: Some(34), ..Default::default()}));
+    assert_eq!(date_strptime(" 3-Feb-2001", "%v"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()}));
+}
+
+#[test]
+pub fn test_strptime_names() {
+    assert_eq!(date_strptime("Saturday February 3 2001", "%A %B %d %Y"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), wday: Some(6), ..Default::default()}));
+    assert_eq!(date_strptime("sat feb 3 2001", "%a %b %d %Y"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), wday: Some(6), ..Default::default()}));
+    assert_eq!(date_strptime("Sat Feb  3 04:05:06 2001", "%c"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), sec: Some(6), wday: Some(6), ..Default::default()}));
+    assert_eq!(date_strptime("Sat Feb  3 04:05:06 UTC 2001", "%+"), Some(DateTime{year: Some(2001), mon: Some(2), mday: Some(3), hour: Some(4), min: Some(5), sec: Some(6), wday: Some(6), zone: Some("UTC".to_owned()), offset: Some(0), ..Default::default()}));
+}
+
+#[test]
+pub fn test_strptime_time() {
+    assert_eq!(date_strptime("23:55:21", "%T"), Some(DateTime{hour: Some(23), min: Some(55), sec: Some(21), ..Default::default()}));
+    assert_eq!(date_strptime("23:55", "%R"), Some(DateTime{hour: Some(23), min: Some(55), ..Default::default()}));
+    assert_eq!(date_strptime("11:55:21 PM", "%r"), Some(DateTime{hour: Some(23), min: Some(55), sec: Some(21), ..Default::default()}));
+    assert_eq!(date_strptime("12:00:00 a.m.", "%I:%M:%S %p"), Some(DateTime{hour: Some(0), min: Some(0), sec: Some(0), ..Default::default()}));
+    assert_eq!(date_strptime(" 9 pm", "%l %P"), Some(DateTime{hour: Some(21), ..Default::default()}));
+    assert_eq!(date_strptime("23:55:21.123", "%T.%L"), Some(DateTime{hour: Some(23), min: Some(55), sec: Some(21), sec_fraction: Some(0.123), ..Default::default()}));
+    assert_eq!(date_strptime("23:55:21.123456789", "%T.%N"), Some(DateTime{hour: Some(23), min: Some(55), sec: Some(21), sec_fraction: Some(0.123456789), ..Default::default()}));
+    assert_eq!(date_strptime("123456", "%L%S"), None);
+    assert_eq!(date_strptime("12345", "%L%S"), Some(DateTime{sec: Some(45), sec_fraction: Some(0.123), ..Default::default()}));
+}
+
+#[test]
+pub fn test_strptime_zone() {
+    assert_eq!(date_strptime("+0900", "%z"), Some(DateTime{zone: Some("+0900".to_owned()), offset: Some(9*3600), ..Default::default()}));
+    assert_eq!(date_strptime("-09:00", "%:z"), Some(DateTime{zone: Some("-09:00".to_owned()), offset: Some(-9*3600), ..Default::default()}));
+    assert_eq!(date_strptime("GMT+09", "%Z"), Some(DateTime{zone: Some("GMT+09".to_owned()), offset: Some(9*3600), ..Default::default()}));
+    assert_eq!(date_strptime("JST", "%Z"), Some(DateTime{zone: Some("JST".to_owned()), offset: Some(9*3600), ..Default::default()}));
+    assert_eq!(date_strptime("Mountain Standard Time", "%Z"), Some(DateTime{zone: Some("Mountain Standard Time".to_owned()), offset: Some(-7*3600), ..Default::default()}));
+}
+
+#[test]
+pub fn test_strptime_week() {
+    assert_eq!(date_strptime("2001-W05-6", "%G-W%V-%u"), Some(DateTime{cwyear: Some(2001), cweek: Some(5), cwday: Some(6), ..Default::default()}));
+    assert_eq!(date_strptime("01-W05-6", "%g-W%V-%u"), Some(DateTime{cwyear: Some(2001), cweek: Some(5), cwday: Some(6), ..Default::default()}));
+    assert_eq!(date_strptime("2001 04 6", "%Y %U %w"), Some(DateTime{year: Some(2001), wnum0: Some(4), wday: Some(6), ..Default::default()}));
+    assert_eq!(date_strptime("2001 05 6", "%Y %W %w"), Some(DateTime{year: Some(2001), wnum1: Some(5), wday: Some(6), ..Default::default()}));
+}
+
+#[test]
+pub fn test_strptime_seconds() {
+    assert_eq!(date_strptime("981173106", "%s"), Some(DateTime{seconds: Some(981173106.0), ..Default::default()}));
+    assert_eq!(date_strptime("-1", "%s"), Some(DateTime{seconds: Some(-1.0), ..Default::default()}));
+    assert_eq!(date_strptime("981173106789", "%Q"), Some(DateTime{seconds: Some(981173106.789), ..Default::default()}));
+}
+
+#[test]
+pub fn test_strptime_invalid() {
+    assert_eq!(date_strptime("2001-13-03", "%Y-%m-%d"), None);
+    assert_eq!(date_strptime("2001-02-32", "%Y-%m-%d"), None);
+    assert_eq!(date_strptime("24:60", "%H:%M"), None);
+    assert_eq!(date_strptime("2001/02/03", "%Y-%m-%d"), None);
+    assert_eq!(date_strptime("01/02/03", "%Y-%m-%d"), None);
+    assert_eq!(date_strptime("99999999999999999999", "%Y"), None);
+    assert_eq!(date_strptime("2001-02-03x", "%Y-%m-%d"), None);
+    assert_eq!(date_strptime2("2001-02-03x", "%Y-%m-%d"), Some((DateTime{year: Some(2001), mon: Some(2), mday: Some(3), ..Default::default()}, "x".to_owned())));
+}
+
+#[test]
+pub fn test_strptime_literals() {
+    assert_eq!(date_strptime("100%", "%Y%%"), Some(DateTime{year: Some(100), ..Default::default()}));
+    assert_eq!(date_strptime("2001   02", "%Y %m"), Some(DateTime{year: Some(2001), mon: Some(2), ..Default::default()}));
+    assert_eq!(date_strptime("%q", "%q"), Some(DateTime::default()));
+}
@@ -4,6 +4,10 @@ use phf::phf_map;
 use regex::{Regex, RegexBuilder};
 use chrono::{offset::TimeZone, DateTime, Datelike, FixedOffset, Timelike};
 
+use super::{DateOrder, ParseOptions, YearPivot};
+use super::relative::Relative;
+use crate::{ErrorKind, ParseError};
+
 
 
 /// Return the number of seconds the specified time zone differs
@@ -25,11 +29,11 @@ use chrono::{offset::TimeZone, DateTime, Datelike, FixedOffset, Timelike};
 /// If +zone_offset+ is unable to determine the offset, nil will be
 /// returned.
 ///
-///
-///     Time.zone_offset("EST") #=> -18000
+/// ```ruby
+/// Time.zone_offset("EST") #=> -18000
+/// ```
 ///
 /// You must require 'time' to use this method.
-
 static ZONE_OFFSET: phf::Map<&'static str, i32> = phf_map! {
     "UTC" => 0,
     //  # ISO 8601
@@ -49,16 +53,16 @@ static ZONE_OFFSET: phf::Map<&'static str, i32> = phf_map! {
 };
 
 
-fn zone_offset(zone: &str, year: Option<i32>) -> Option<i32> {
+fn zone_offset(zone: &str, year: Option<i32>, local: &LocalZone) -> Option<i32> {
     let zone = zone.to_ascii_uppercase();
 
     lazy_static! {
-        static ref ZONE_NUM_REGEX: Regex = RegexBuilder::new(r"\A([+-])(\d\d)(:?)(\d\d)(?:(:?)(\d\d))?\z")
+        static ref ZONE_NUM_REGEX: Regex = RegexBuilder::new(r"\A([+-])([0-9][0-9])(:?)([0-9][0-9])(?:(:?)([0-9][0-9]))?\z")
             .case_insensitive(true)
             .ignore_whitespace(true)
             .build()
             .unwrap();
-        static ref ZONE_MATCH2_REGEX: Regex = RegexBuilder::new(r"\A[+-]\d\d\z")
+        static ref ZONE_MATCH2_REGEX: Regex = RegexBuilder::new(r"\A[+-][0-9][0-9]\z")
             .case_insensitive(true)
             .ignore_whitespace(true)
             .build()
@@ -73,44 +77,40 @@ fn zone_offset(zone: &str, year: Option<i32>) -> Option<i32> {
         //                              |
         //                             this( \3 )
         if captures.get(5).is_none() || (captures.get(3).map(|x| x.as_str()) == captures.get(5).map(|x| x.as_str())) {
-            let sign = if captures.get(1).unwrap().as_str() == "-" {
+            let sign = if captures.get(1)?.as_str() == "-" {
                 -1
             } else {
                 1
             };
-            let hour = captures.get(2).unwrap().as_str().parse::<i32>().unwrap();
-            let mins = captures.get(4).map(|x| x.as_str().parse::<i32>().unwrap_or_default()).unwrap_or_default();
-            let secs = captures.get(6).map(|x| x.as_str().parse::<i32>().unwrap_or_default()).unwrap_or_default();
+            let hour = captures.get(2)?.as_str().parse::<i32>().ok()?;
+            let mins = captures.get(4).map(|x| x.as_str().parse::<i32>()).transpose().ok()?.unwrap_or_default();
+            let secs = captures.get(6).map(|x| x.as_str().parse::<i32>()).transpose().ok()?.unwrap_or_default();
             let offset = Some(sign * (( hour * 60 + mins) * 60 + secs));
             return offset;
         }
     }
     if ZONE_MATCH2_REGEX.is_match(&zone) {
-        return Some(zone.parse::<i32>().unwrap() * 3600);
+        return zone.parse::<i32>().ok().map(|x| x * 3600);
     }
     if let Some(offset) = ZONE_OFFSET.get(&zone) {
         return Some(offset * 3600);
     }
 
-    let year = year.unwrap_or_else(|| {
-        let now_fixed_offset: chrono::DateTime<chrono::FixedOffset> = chrono::Local::now().into();
-        now_fixed_offset.year()
-    });
+    let tz = local.tz;
+    let year = year.unwrap_or_else(|| chrono::Utc::now().with_timezone(&tz).year());
 
-    if let Ok(tz_str) = iana_time_zone::get_timezone() {
-        if let Ok(tz) = tz_str.parse::<chrono_tz::Tz>() {
-            let dt = tz.ymd(year, 1, 1).and_hms(0, 0, 0);
-            let off = dt.offset().to_string();
-            if off.to_uppercase() == zone {
-                return Some(dt.fixed_offset().offset().local_minus_utc());
-            }
+    if let Some(dt) = tz.with_ymd_and_hms(year, 1, 1, 0, 0, 0).earliest() {
+        let off = dt.offset().to_string();
+        if off.to_uppercase() == zone {
+            return Some(dt.fixed_offset().offset().local_minus_utc());
+        }
+    }
 
-            let dt = tz.ymd(year, 7, 1).and_hms(0, 0, 0);
-            let off = dt.offset().to_string();
-            if off.to_uppercase() == zone {
-                return Some(dt.fixed_offset().offset().local_minus_utc());
-            }
-        };
+    if let Some(dt) = tz.with_ymd_and_hms(year, 7, 1, 0, 0, 0).earliest() {
+        let off = dt.offset().to_string();
+        if off.to_uppercase() == zone {
+            return Some(dt.fixed_offset().offset().local_minus_utc());
+        }
     }
     None
 }
@@ -148,9 +148,9 @@ fn is_zone_utc(zone: &str) -> bool {
 const LEAP_YEAR_MONTH_DAYS: [i32;12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
 const COMMON_YEAR_MONTH_DAYS: [i32;12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
 
-fn month_days(y: i32, m: i32) -> crate::Result<i32> {
+pub(super) fn month_days(y: i32, m: i32) -> crate::Result<i32> {
     if !(1..=12).contains(&m) {
-        return Err(crate::ParseError::OutOfRangeError("month".to_owned(), m.to_string()));
+        return Err(ParseError::out_of_range(ErrorKind::FieldOutOfRange, "mon", m, Some(1..=12)));
     }
     if ((y % 4 == 0) && (y % 100 != 0)) || (y % 400 == 0) {
         Ok(LEAP_YEAR_MONTH_DAYS[(m-1) as usize])
@@ -173,8 +173,8 @@ fn apply_offset(
     mut min: i32,
     mut sec: i32,
     mut off: i32,
-) -> (i32, i32, i32, i32, i32, i32) {
-    let mut o: i32 = 0;
+) -> crate::Result<(i32, i32, i32, i32, i32, i32)> {
+    let mut o: i32;
     if off < 0 {
         off = -off;
 
@@ -241,38 +241,64 @@ fn apply_offset(
                     year -= 1;
                     mon = 12;
                 }
-                day = month_days(year, mon).unwrap();
+                day = month_days(year, mon)?;
             }
         }
     }
-    (year, mon, day, hour, min, sec)
+    Ok((year, mon, day, hour, min, sec))
 }
 
 
-fn validated_ymd(year: i32, month: i32, day: i32) -> crate::Result<(i32, u32, u32)> {
-    if !(1..=12).contains(&month) {
-        return Err(crate::ParseError::OutOfRangeError("month".to_owned(), month.to_string()));
-    }
+pub(super) fn validated_ymd(year: i32, month: i32, day: i32) -> crate::Result<(i32, u32, u32)> {
     let max_days = month_days(year, month)?;
     if !(1..=max_days).contains(&day) {
-        return Err(crate::ParseError::OutOfRangeError("day".to_owned(), day.to_string()));
+        let kind = if (1..=31).contains(&day) { ErrorKind::InvalidDayOfMonth } else { ErrorKind::FieldOutOfRange };
+        return Err(ParseError::out_of_range(kind, "mday", day, Some(1..=max_days as i64)));
     }
     Ok((year, month as u32, day as u32))
 }
 
 fn validated_hms(hour: i32, min: i32, sec: i32) -> crate::Result<(u32, u32, u32)> {
     if !(0..=23).contains(&hour) {
-        return Err(crate::ParseError::OutOfRangeError("hour".to_owned(), hour.to_string()));
+        return Err(ParseError::out_of_range(ErrorKind::FieldOutOfRange, "hour", hour, Some(0..=23)));
     }
     if !(0..=59).contains(&min) {
-        return Err(crate::ParseError::OutOfRangeError("minute".to_owned(), min.to_string()));
+        return Err(ParseError::out_of_range(ErrorKind::FieldOutOfRange, "min", min, Some(0..=59)));
     }
     if !(0..=59).contains(&sec) {
-        return Err(crate::ParseError::OutOfRangeError("second".to_owned(), sec.to_string()));
+        return Err(ParseError::out_of_range(ErrorKind::FieldOutOfRange, "sec", sec, Some(0..=59)));
     }
 
     Ok((hour as u32, min as u32, sec as u32))
 }
+
+// The fields come from the parsers as u32, which may not fit apply_offset.
+pub(super) fn field_i32(field: &'static str, value: u32) -> crate::Result<i32> {
+    i32::try_from(value).map_err(|_| ParseError::out_of_range(ErrorKind::FieldOutOfRange, field, value, None))
+}
+
+// apply_offset carries between the fields, which takes them to be about in
+// range; the exact checks come after it.
+fn carry_bounds(year: i32, mon: i32, mday: i32, hour: i32, min: i32, sec: i32) -> crate::Result<()> {
+    let bounds: [(&'static str, i32, std::ops::RangeInclusive<i32>); 5] = [
+        ("mon", mon, 1..=12), ("mday", mday, 1..=31), ("hour", hour, 0..=24), ("min", min, 0..=60), ("sec", sec, 0..=60),
+    ];
+    for (field, value, range) in bounds {
+        if !range.contains(&value) {
+            let allowed = i64::from(*range.start())..=i64::from(*range.end());
+            return Err(ParseError::out_of_range(ErrorKind::FieldOutOfRange, field, value, Some(allowed)));
+        }
+    }
+    if chrono::NaiveDate::from_ymd_opt(year, 1, 1).is_none() {
+        return Err(unrepresentable_year(year));
+    }
+    Ok(())
+}
+
+pub(super) fn unrepresentable_year(year: i32) -> ParseError {
+    let allowed = chrono::NaiveDate::MIN.year() as i64..=chrono::NaiveDate::MAX.year() as i64;
+    ParseError::out_of_range(ErrorKind::UnrepresentableYear, "year", year, Some(allowed))
+}
 // Takes a string representation of a Time and attempts to parse it
 // using a heuristic.
 //
@@ -393,43 +419,165 @@ fn validated_hms(hour: i32, min: i32, sec: i32) -> crate::Result<(u32, u32, u32)
 //   end
 // end
 
-fn to_local_time(t: chrono::DateTime<chrono::FixedOffset>, offset: Option<i32>) -> crate::Result<chrono::DateTime<chrono::FixedOffset>> {
+/// How a wall clock time is resolved when it falls into a daylight saving
+/// time transition of the zone it is interpreted in.
+///
+/// A time in a gap (such as 02:30 on the night clocks spring forward)
+/// doesn't exist, while a time in an overlap (01:30 on the night clocks fall
+/// back) happens twice.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DstPolicy {
+    /// Takes the earlier instant in an overlap and moves a time in a gap
+    /// forward by the length of the gap (02:30 becomes 03:30), like Ruby's
+    /// Time.local does.
+    #[default]
+    Compatible,
+    /// Takes the earlier instant in an overlap and moves a time in a gap
+    /// backward by the length of the gap (02:30 becomes 01:30).
+    Earlier,
+    /// Takes the later instant in an overlap and moves a time in a gap
+    /// forward by the length of the gap.
+    Later,
+    /// Fails with SkippedLocalTime or AmbiguousLocalTime.
+    Reject,
+}
+
+/// What is done when the day of the week in the input, as in "Sat, 01 Jun
+/// 2000", isn't the day of the week of the date (a Thursday).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WeekdayPolicy {
+    /// Goes by the date and disregards the day of the week, like Ruby's
+    /// Time.parse does.
+    #[default]
+    Ignore,
+    /// Goes by the date, and reports a WeekdayMismatch error to the
+    /// `on_warning` callback of the options.
+    Warn,
+    /// Fails with WeekdayMismatch.
+    Reject,
+    /// Goes by the day of the week, taking the nearest date that falls on
+    /// it, at most three days away (Saturday, 3 June 2000).
+    PreferWeekday,
+}
+
+// The time zone that times without a zone are read in, which is the one
+// of TZ or the system time zone unless given by parse_in_zone.
+#[derive(Clone, Copy)]
+pub(super) struct LocalZone {
+    tz: chrono_tz::Tz,
+    dst: DstPolicy,
+}
+
+impl LocalZone {
+    fn system() -> LocalZone {
+        LocalZone { tz: crate::get_current_timezone(), dst: DstPolicy::default() }
+    }
+
+    pub(super) fn from_options(options: &ParseOptions) -> LocalZone {
+        match options.zone {
+            Some(tz) => LocalZone { tz, dst: options.dst_policy },
+            None => LocalZone { dst: options.dst_policy, ..LocalZone::system() },
+        }
+    }
+
+    pub(super) fn now(&self) -> DateTime<FixedOffset> {
+        chrono::Utc::now().with_timezone(&self.tz).fixed_offset()
+    }
+
+    // Resolves a wall clock time in the zone following the DST policy.
+    fn resolve_local(&self, naive: &chrono::NaiveDateTime) -> crate::Result<DateTime<FixedOffset>> {
+        use chrono::{LocalResult, Offset};
+
+        match self.tz.from_local_datetime(naive) {
+            LocalResult::Single(dt) => Ok(dt.fixed_offset()),
+            LocalResult::Ambiguous(earliest, latest) => match self.dst {
+                DstPolicy::Compatible | DstPolicy::Earlier => Ok(earliest.fixed_offset()),
+                DstPolicy::Later => Ok(latest.fixed_offset()),
+                DstPolicy::Reject => Err(ParseError::new(ErrorKind::AmbiguousLocalTime).with_value(naive).with_detail(self.tz.name())),
+            },
+            LocalResult::None => {
+                // The offsets in effect before and after the gap. Reading the
+                // time with the offset before the gap lands past it and the
+                // other way around.
+                let day = chrono::Duration::days(1);
+                let offset_at = |t: Option<chrono::NaiveDateTime>| t.map(|t| self.tz.offset_from_utc_datetime(&t).fix());
+                let (before, after) = match (offset_at(naive.checked_sub_signed(day)), offset_at(naive.checked_add_signed(day))) {
+                    (Some(before), Some(after)) => (before, after),
+                    _ => return Err(unrepresentable_year(naive.year())),
+                };
+                let offset = match self.dst {
+                    DstPolicy::Compatible | DstPolicy::Later => before,
+                    DstPolicy::Earlier => after,
+                    DstPolicy::Reject => return Err(ParseError::new(ErrorKind::SkippedLocalTime).with_value(naive).with_detail(self.tz.name())),
+                };
+                offset.from_local_datetime(naive)
+                    .single()
+                    .map(|dt| dt.with_timezone(&self.tz).fixed_offset())
+                    .ok_or_else(|| unrepresentable_year(naive.year()))
+            }
+        }
+    }
+}
+
+fn to_local_time(t: chrono::DateTime<chrono::FixedOffset>, offset: Option<i32>, local: &LocalZone) -> crate::Result<chrono::DateTime<chrono::FixedOffset>> {
     if let Some(off) = offset {
-        let fixed_offset = FixedOffset::east_opt(off).ok_or(crate::ParseError::OffsetOutOfBounds)?;
+        let fixed_offset = FixedOffset::east_opt(off)
+            .ok_or_else(|| ParseError::out_of_range(ErrorKind::OffsetOutOfBounds, "offset", off, Some(-86_399..=86_399)))?;
         return Ok(t.with_timezone(&fixed_offset));
     }
 
-    let now_fixed_offset: chrono::DateTime<chrono::FixedOffset> = chrono::Local::now().into();
-    let local_offset = now_fixed_offset.offset();
-    Ok(t.with_timezone(local_offset))
+    // The offset of the zone at +t+ itself, which differs from the current
+    // one across DST transitions.
+    Ok(t.with_timezone(&local.tz).fixed_offset())
 }
 
-fn force_zone(time: chrono::DateTime<chrono::FixedOffset>, zone: &str, offset: Option<i32>) -> crate::Result<chrono::DateTime<chrono::FixedOffset>> {
+fn force_zone(time: chrono::DateTime<chrono::FixedOffset>, zone: &str, offset: Option<i32>, local: &LocalZone) -> crate::Result<chrono::DateTime<chrono::FixedOffset>> {
     if is_zone_utc(zone) {
         let res = time.to_utc();
         return Ok(res.fixed_offset());
     }
     let offset = if offset.is_none() {
-        zone_offset(zone, None)
+        zone_offset(zone, None, local)
     } else {
         offset
     };
 
     if let Some(off) = offset {
-        let time = to_local_time(time, None)?;
+        let time = to_local_time(time, None, local)?;
 
         if time.offset().local_minus_utc() != off {
-            return to_local_time(time, offset);
+            return to_local_time(time, offset, local);
         } else {
             return Ok(time);
         }
     }
-    to_local_time(time, None)
+    to_local_time(time, None, local)
 }
 
 
+// Checks the fields and builds the time, reporting +date+ as the input of
+// the errors.
 #[allow(clippy::too_many_arguments)]
 fn make_time(
+    date: &str,
+    year: Option<i32>,
+    yday: Option<i32>,
+    mon: Option<u32>,
+    mday: Option<u32>,
+    hour: Option<u32>,
+    min: Option<u32>,
+    sec: Option<u32>,
+    sec_fraction: Option<f64>,
+    zone: Option<&str>,
+    now: Option<chrono::DateTime<FixedOffset>>,
+    local: &LocalZone,
+) -> crate::Result<chrono::DateTime<FixedOffset>> {
+    make_time_fields(date, year, yday, mon, mday, hour, min, sec, sec_fraction, zone, now, local)
+        .map_err(|err| err.with_input(date))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn make_time_fields(
     date: &str,
     mut year: Option<i32>,
         yday: Option<i32>,
@@ -441,6 +589,7 @@ fn make_time(
     sec_fraction: Option<f64>,
     zone: Option<&str>,
     mut now: Option<chrono::DateTime<FixedOffset>>,
+    local: &LocalZone,
 ) -> crate::Result<chrono::DateTime<FixedOffset>> {
     if yday.is_none() &&
         mon.is_none() &&
@@ -450,7 +599,13 @@ fn make_time(
         sec.is_none() &&
         sec_fraction.is_none()
     {
-        return Err(crate::ParseError::MissingTimeInformationError(date.to_owned()));
+        return Err(ParseError::new(ErrorKind::NoDateFound));
+    }
+
+    // A time with an IANA zone name is a wall clock time in that zone,
+    // whose offset depends on the instant.
+    if let Some(tz) = zone.and_then(super::zonetab::iana_zone) {
+        return make_time(date, year, yday, mon, mday, hour, min, sec, sec_fraction, None, now, &LocalZone { tz, dst: local.dst });
     }
 
     let mut off = None;
@@ -463,19 +618,19 @@ fn make_time(
         };
 
         if let Some(zone) = &zone {
-            off = zone_offset(zone, off_year);
+            off = zone_offset(zone, off_year, local);
         }
     }
 
     if let Some(yday) = &yday {
         if !(1..=366).contains(yday) {
-            return Err(crate::ParseError::OutOfRangeError("yday".to_owned(), yday.to_string()));
+            return Err(ParseError::out_of_range(ErrorKind::InvalidYday, "yday", yday, Some(1..=366)));
         }
 
         let mut mon = (yday -1).div_euclid(31) + 1;
         let mut mday: i32 = (yday -1).rem_euclid(31) + 1;
 
-        let t = make_time(date, year, None, Some(mon as u32), Some(mday as u32), hour, min, sec, sec_fraction, zone, now)?;
+        let t = make_time(date, year, None, Some(mon as u32), Some(mday as u32), hour, min, sec, sec_fraction, zone, now, local)?;
         let diff = yday - (t.ordinal() as i32);
         if diff == 0 {
             return Ok(t);
@@ -483,16 +638,17 @@ fn make_time(
         mday += diff;
 
         if mday > 28 {
-            let mon_mday = month_days(year.unwrap(), mon)?;
+            let mon_mday = month_days(t.year(), mon)?;
             if mday > mon_mday {
                 mon += 1;
                 if mon > 12 {
-                    return Err(crate::ParseError::OutOfRangeError("yday".to_owned(), yday.to_string()));
+                    let days = if t.date_naive().leap_year() { 366 } else { 365 };
+                    return Err(ParseError::out_of_range(ErrorKind::InvalidYday, "yday", yday, Some(1..=days)));
                 }
                 mday -= mon_mday;
             }
         }
-        return make_time(date, year, None, Some(mon as u32), Some(mday as u32), hour, min, sec, sec_fraction, zone, now);
+        return make_time(date, year, None, Some(mon as u32), Some(mday as u32), hour, min, sec, sec_fraction, zone, now, local);
     }
 
     if let Some(now_dt) = &now {
@@ -503,9 +659,7 @@ fn make_time(
                 }
             }
         } else {
-            let now_fixed_offset: chrono::DateTime<chrono::FixedOffset> = chrono::Local::now().into();
-            let local_offset = now_fixed_offset.offset();
-            now = Some(now_dt.with_timezone(local_offset));
+            now = Some(now_dt.with_timezone(&local.tz).fixed_offset());
         }
     }
     let mut nanosec = None;
@@ -557,24 +711,131 @@ fn make_time(
     if Some(year) != off_year {
         off = None;
         if let Some(zone) = zone {
-            off = zone_offset(zone, Some(year));
+            off = zone_offset(zone, Some(year), local);
         }
     }
     if let Some(offset) = off {
-        let (year, mon, mday, hour, min, sec) = apply_offset(year, mon.try_into().unwrap(), mday.try_into().unwrap(), hour.try_into().unwrap(), min.try_into().unwrap(), sec.try_into().unwrap(), off.unwrap());
+        let (mon, mday, hour, min, sec) = (field_i32("mon", mon)?, field_i32("mday", mday)?, field_i32("hour", hour)?, field_i32("min", min)?, field_i32("sec", sec)?);
+        carry_bounds(year, mon, mday, hour, min, sec)?;
+        let (year, mon, mday, hour, min, sec) = apply_offset(year, mon, mday, hour, min, sec, offset)?;
         let (year, mon, mday) = validated_ymd(year, mon, mday)?;
         let (hour, min, sec) = validated_hms(hour, min, sec)?;
-        let dt: DateTime<FixedOffset> = chrono::Utc.ymd(year, mon, mday).and_hms_nano_opt(hour, min, sec, nanosec as u32).unwrap().fixed_offset();
-        force_zone(dt, zone.unwrap(), Some(offset))
+        let dt: DateTime<FixedOffset> = chrono::NaiveDate::from_ymd_opt(year, mon, mday)
+            .and_then(|d| d.and_hms_nano_opt(hour, min, sec, nanosec as u32))
+            .ok_or_else(|| unrepresentable_year(year))?
+            .and_utc()
+            .fixed_offset();
+        force_zone(dt, zone.unwrap_or_default(), Some(offset), local)
     } else {
-        let (year, mon, mday) = validated_ymd(year, mon as i32, mday as i32)?;
-        let (hour, min, sec) = validated_hms(hour as i32, min as i32, sec as i32)?;
-        let dt = chrono::Local.ymd(year, mon, mday).and_hms_nano_opt(hour, min, sec, nanosec as u32).unwrap().fixed_offset();
-        Ok(dt)
+        let (year, mon, mday) = validated_ymd(year, field_i32("mon", mon)?, field_i32("mday", mday)?)?;
+        let (hour, min, sec) = validated_hms(field_i32("hour", hour)?, field_i32("min", min)?, field_i32("sec", sec)?)?;
+        let naive = chrono::NaiveDate::from_ymd_opt(year, mon, mday)
+            .and_then(|d| d.and_hms_nano_opt(hour, min, sec, nanosec as u32))
+            .ok_or_else(|| unrepresentable_year(year))?;
+        local.resolve_local(&naive)
+    }
+}
+
+
+// Same as +parse+, with the knobs of +options+: the time missing components
+// are taken from, a block converting the year, how two digit years are
+// completed, the order of numeric dates and the zone (and its DST policy)
+// times without a zone are taken in.
+//
+// Relative dates such as "tomorrow at 10:00", "3 days ago" and "next
+// Friday" are resolved against the time missing components are taken from,
+// unless the input also has a date.
+//
+//     let options = ParseOptions::new().year_fn(move |y| y + tenant_century);
+//     parse_with_options("01-10-31", &options)
+pub fn parse_with_options(date: &str, options: &ParseOptions) -> crate::Result<DateTime<FixedOffset>>
+{
+    let local = LocalZone::from_options(options);
+    let now = options.now.unwrap_or_else(|| local.now());
+    let pivot = options.year_pivot_at(now.year());
+    if let Some((relative, rest)) = super::relative::parse_relative(date, &now) {
+        let (d, _) = super::parse::date_parse_internal(&rest, pivot, options.date_order);
+        if !has_date(&d) {
+            return relative_time(date, relative.map_err(|err| err.with_input(date))?, &d, now, &local);
+        }
+    }
+    let (d, _) = super::parse::date_parse_internal(date, pivot, options.date_order);
+    let mut year = d.year;
+    if let (Some(y), Some(func)) = (year, &options.year_fn) {
+        year = Some(func(y));
+    }
+    let located = |err| located(err, date, pivot, options.date_order);
+    let t = make_week_time(date, &d, year, now, &local).map_err(located)?;
+    let Some(err) = weekday_mismatch(&d, &t) else {
+        return Ok(t);
+    };
+    match options.weekday_policy {
+        WeekdayPolicy::Ignore => Ok(t),
+        WeekdayPolicy::Warn => {
+            if let Some(on_warning) = &options.on_warning {
+                on_warning(&located(err));
+            }
+            Ok(t)
+        }
+        WeekdayPolicy::Reject => Err(located(err)),
+        WeekdayPolicy::PreferWeekday => {
+            let wday = d.wday.unwrap_or_default();
+            let days = (wday - t.weekday().num_days_from_sunday() as i32 + 3).rem_euclid(7) - 3;
+            let day = t.date_naive().checked_add_signed(chrono::Duration::days(days as i64))
+                .ok_or_else(|| located(unrepresentable_year(t.year())))?;
+            make_time(date, Some(day.year()), None, Some(day.month()), Some(day.day()), d.hour, d.min, d.sec, d.sec_fraction, d.zone.as_deref(), Some(now), &local)
+                .map_err(located)
+        }
     }
 }
 
+pub(super) fn has_date(d: &crate::DateTime) -> bool {
+    d.year.is_some() || d.mon.is_some() || d.mday.is_some() || d.yday.is_some() || d.cwyear.is_some() || d.cweek.is_some() ||
+        d.cwday.is_some() || d.wnum0.is_some() || d.wnum1.is_some()
+}
+
+// The time of a relative expression, with the time of day and zone of +d+,
+// which was read from the rest of the input, as in "tomorrow at 10:00 UTC".
+// An instant such as "3 days ago" is kept as it is when there is no time
+// of day.
+fn relative_time(date: &str, relative: Relative, d: &crate::DateTime, now: DateTime<FixedOffset>, local: &LocalZone) -> crate::Result<DateTime<FixedOffset>> {
+    let timed = d.hour.is_some() || d.min.is_some() || d.sec.is_some() || d.sec_fraction.is_some();
+    if let (Relative::Instant(t), false) = (relative, timed) {
+        return Ok(t);
+    }
+    let day = relative.date();
+    make_time(date, Some(day.year()), None, Some(day.month()), Some(day.day()), d.hour, d.min, d.sec, d.sec_fraction, d.zone.as_deref(), Some(now), local)
+}
+
+// Adds the input and the span of the field of +err+ to it. Parsing again to
+// tell where the field came from is only worth it on failure.
+pub(super) fn located(err: ParseError, date: &str, pivot: Option<YearPivot>, order: Option<DateOrder>) -> ParseError {
+    let field = match err.kind() {
+        ErrorKind::SkippedLocalTime | ErrorKind::AmbiguousLocalTime => Some("hour"),
+        ErrorKind::OffsetOutOfBounds => Some("zone"),
+        _ => err.field(),
+    };
+    let span = field.and_then(|field| {
+        let mut trace = Some(super::spans::Trace::new(date));
+        super::parse::date_parse_traced(date, pivot, order, &mut trace);
+        trace?.spans.into_iter().find(|x| x.field == field).map(|x| x.range())
+    });
+    err.with_input(date).with_span(span)
+}
 
+// The error for a day of the week in +d+ that +t+ doesn't fall on.
+fn weekday_mismatch(d: &crate::DateTime, t: &DateTime<FixedOffset>) -> Option<ParseError> {
+    let wday = d.wday?;
+    let weekday = t.weekday();
+    if weekday.num_days_from_sunday() as i32 == wday {
+        return None;
+    }
+    let name = u8::try_from((wday + 6).rem_euclid(7)).ok().and_then(|x| chrono::Weekday::try_from(x).ok())?;
+    Some(ParseError::out_of_range(ErrorKind::WeekdayMismatch, "wday", name, None)
+        .with_detail(format!("{} ({})", t.date_naive(), weekday)))
+}
+
+// Prefer +parse_with_options+, which takes closures for the year.
 pub fn parse_with_custom_time_and_year(
     date: &str,
     now: Option<chrono::DateTime<FixedOffset>>,
@@ -582,17 +843,12 @@ pub fn parse_with_custom_time_and_year(
 )
 -> crate::Result<DateTime<FixedOffset>>
 {
-    let now = now.unwrap_or_else(|| chrono::Local::now().fixed_offset());
-    let comp = year_fn.is_none();
-    let d = crate::date_parser::date_parse(date, comp);
-    let mut year = d.year;
-    if year.is_some() && !comp {
-        if let Some(func) = year_fn {
-            year = Some(func(year.unwrap()));
-        }
+    let mut options = ParseOptions::new();
+    options.now = now;
+    if let Some(year_fn) = year_fn {
+        options = options.year_fn(year_fn);
     }
-    let time = make_time(date, year, d.yday, d.mon, d.mday, d.hour, d.min, d.sec, d.sec_fraction, d.zone.as_deref(), Some(now));
-    time
+    parse_with_options(date, &options)
 }
 
 pub fn parse(date: &str,) -> crate::Result<DateTime<FixedOffset>>
@@ -600,42 +856,398 @@ pub fn parse(date: &str,) -> crate::Result<DateTime<FixedOffset>>
     parse_with_custom_time_and_year(date, None, None)
 }
 
+// Same as +parse_with_custom_time_and_year+, but two digit years are
+// completed following +pivot+ instead of the 69 cutoff. Unlike a year
+// block it only applies to two digit years, and YearPivot::ClosestToNow
+// is relative to +now+.
+//
+//     # within 50 years of the record's creation
+//     parse_with_year_pivot("Dec 31 75", None, YearPivot::ClosestTo(2030))
+//     #=> 2075-12-31 00:00:00 +0000
+pub fn parse_with_year_pivot(
+    date: &str,
+    now: Option<chrono::DateTime<FixedOffset>>,
+    pivot: YearPivot,
+)
+-> crate::Result<DateTime<FixedOffset>>
+{
+    let mut options = ParseOptions::new().year_pivot(pivot);
+    options.now = now;
+    parse_with_options(date, &options)
+}
+
+// Same as +parse+, but a +date+ without a time zone is taken as the wall
+// clock time in +tz+ instead of the system time zone, and the result is
+// returned in +tz+. A +date+ with a time zone or offset keeps its instant
+// and is converted to +tz+. Missing components are supplied from the
+// current time in +tz+.
+//
+// A wall clock time falling into a DST gap or overlap of +tz+ is resolved
+// following +dst+.
+//
+//     parse_in_zone_with_dst_policy("2024-03-10 02:30", New_York, DstPolicy::Compatible)
+//     #=> 2024-03-10 03:30:00 EDT
+//     parse_in_zone_with_dst_policy("2024-11-03 01:30", New_York, DstPolicy::Later)
+//     #=> 2024-11-03 01:30:00 EST
+pub fn parse_in_zone_with_dst_policy(date: &str, tz: chrono_tz::Tz, dst: DstPolicy) -> crate::Result<DateTime<chrono_tz::Tz>>
+{
+    let t = parse_with_options(date, &ParseOptions::new().zone(tz).dst_policy(dst))?;
+    Ok(t.with_timezone(&tz))
+}
+
+// Same as +parse_in_zone_with_dst_policy+ with DstPolicy::Compatible.
+//
+//     parse_in_zone("2024-03-10 02:30", New_York) #=> 2024-03-10 03:30:00 EDT
+pub fn parse_in_zone(date: &str, tz: chrono_tz::Tz) -> crate::Result<DateTime<chrono_tz::Tz>>
+{
+    parse_in_zone_with_dst_policy(date, tz, DstPolicy::default())
+}
+
+// make_time doesn't deal with cwyear/cweek/cwday or the %U/%W week numbers,
+// so those are turned into a calendar date first. Missing upper fields are
+// taken from +now+ and missing lower fields default to the start of the
+// period, as Date.strptime would do. A day of the week with no date, as in
+// "Fri 10:00", is that day of the week of +now+, Sunday to Saturday, as
+// Date.parse takes it.
+pub(super) fn week_based_date(d: &crate::DateTime, now: &DateTime<FixedOffset>) -> crate::Result<Option<chrono::NaiveDate>> {
+    let wday_only = d.wday.is_some() && d.year.is_none() && d.yday.is_none() && d.mon.is_none() && d.mday.is_none() &&
+        d.cwyear.is_none() && d.cweek.is_none() && d.cwday.is_none() && d.wnum0.is_none() && d.wnum1.is_none();
+    if let (true, Some(wday)) = (wday_only, d.wday) {
+        let days = wday as i64 - now.weekday().num_days_from_sunday() as i64;
+        return Ok(now.date_naive().checked_add_signed(chrono::Duration::days(days)));
+    }
+    if (d.cwyear.is_some() && d.year.is_none()) || ((d.cwday.is_some() || d.cweek.is_some()) && !(d.mon.is_some() && d.mday.is_some())) {
+        let cwyear = d.cwyear.or(d.year).unwrap_or_else(|| now.iso_week().year());
+        let cweek = d.cweek.unwrap_or_else(|| if d.cwyear.is_some() { 1 } else { now.iso_week().week() });
+        let cwday = d.cwday
+            .or_else(|| d.wday.map(|w| if w == 0 { 7 } else { w as u32 }))
+            .unwrap_or(1);
+        let weekday = cwday.checked_sub(1).and_then(|x| u8::try_from(x).ok()).and_then(|x| chrono::Weekday::try_from(x).ok())
+            .ok_or_else(|| ParseError::out_of_range(ErrorKind::FieldOutOfRange, "cwday", cwday, Some(1..=7)))?;
+        return chrono::NaiveDate::from_isoywd_opt(cwyear, cweek, weekday)
+            .map(Some)
+            .ok_or_else(|| {
+                let weeks = chrono::NaiveDate::from_ymd_opt(cwyear, 12, 28).map(|x| x.iso_week().week() as i64);
+                ParseError::out_of_range(ErrorKind::FieldOutOfRange, "cweek", cweek, weeks.map(|weeks| 1..=weeks))
+            });
+    }
+
+    if (d.wnum0.is_some() || d.wnum1.is_some()) && d.yday.is_none() && !(d.mon.is_some() && d.mday.is_some()) {
+        let year = d.year.unwrap_or_else(|| now.year());
+        let jan1 = chrono::NaiveDate::from_ymd_opt(year, 1, 1)
+            .ok_or_else(|| unrepresentable_year(year))?;
+        // Week 1 starts on the first Sunday (%U) or Monday (%W) of the year;
+        // the days before it belong to week 0.
+        let days = if let Some(wnum0) = d.wnum0 {
+            let wday = d.wday.unwrap_or(0) as i64;
+            let first = (7 - jan1.weekday().num_days_from_sunday() as i64) % 7;
+            7 * (wnum0 as i64 - 1) + first + wday
+        } else {
+            let wday = d.wday.unwrap_or(1) as i64;
+            let first = (7 - jan1.weekday().num_days_from_monday() as i64) % 7;
+            7 * (d.wnum1.unwrap_or_default() as i64 - 1) + first + (wday + 6) % 7
+        };
+        return jan1.checked_add_signed(chrono::Duration::days(days))
+            .filter(|date| date.year() == year)
+            .map(Some)
+            .ok_or_else(|| {
+                let field = if d.wnum0.is_some() { "wnum0" } else { "wnum1" };
+                ParseError::out_of_range(ErrorKind::FieldOutOfRange, field, d.wnum0.or(d.wnum1).unwrap_or_default(), Some(0..=53))
+            });
+    }
+    Ok(None)
+}
+
+// Same as make_time, but resolves the week based fields of +d+ first.
+pub(super) fn make_week_time(date: &str, d: &crate::DateTime, year: Option<i32>, now: DateTime<FixedOffset>, local: &LocalZone) -> crate::Result<DateTime<FixedOffset>> {
+    let (year, yday, mon, mday) = match week_based_date(d, &now)? {
+        Some(date) => (Some(date.year()), None, Some(date.month()), Some(date.day())),
+        None => (year, d.yday, d.mon, d.mday),
+    };
+    make_time(date, year, yday, mon, mday, d.hour, d.min, d.sec, d.sec_fraction, d.zone.as_deref(), Some(now), local)
+}
+
+// Works similar to +parse+ except that instead of using a heuristic to
+// detect the format of the input string, you provide a second argument
+// that describes the format of the string.
+//
+// Raises ArgumentError if the date or format is invalid.
+//
+// If a block is given, the year described in +date+ is converted by the
+// block.  For example:
+//
+//     Time.strptime(...) {|y| y < 100 ? (y >= 69 ? y + 1900 : y + 2000) : y}
+//
+// Below is a list of the formatting options:
+//
+// %a :: The abbreviated weekday name ("Sun")
+// %A :: The  full  weekday  name ("Sunday")
+// %b :: The abbreviated month name ("Jan")
+// %B :: The  full  month  name ("January")
+// %c :: The preferred local date and time representation
+// %C :: Century (20 in 2009)
+// %d :: Day of the month (01..31)
+// %D :: Date (%m/%d/%y)
+// %e :: Day of the month, blank-padded ( 1..31)
+// %F :: Equivalent to %Y-%m-%d (the ISO 8601 date format)
+// %g :: The last two digits of the commercial year
+// %G :: The week-based year according to ISO-8601 (week 1 starts on Monday
+//       and includes January 4)
+// %h :: Equivalent to %b
+// %H :: Hour of the day, 24-hour clock (00..23)
+// %I :: Hour of the day, 12-hour clock (01..12)
+// %j :: Day of the year (001..366)
+// %k :: hour, 24-hour clock, blank-padded ( 0..23)
+// %l :: hour, 12-hour clock, blank-padded ( 0..12)
+// %L :: Millisecond of the second (000..999)
+// %m :: Month of the year (01..12)
+// %M :: Minute of the hour (00..59)
+// %n :: Newline (\n)
+// %N :: Fractional seconds digits
+// %p :: Meridian indicator ("AM" or "PM")
+// %P :: Meridian indicator ("am" or "pm")
+// %r :: time, 12-hour (same as %I:%M:%S %p)
+// %R :: time, 24-hour (%H:%M)
+// %s :: Number of seconds since 1970-01-01 00:00:00 UTC.
+// %S :: Second of the minute (00..60)
+// %t :: Tab character (\t)
+// %T :: time, 24-hour (%H:%M:%S)
+// %u :: Day of the week as a decimal, Monday being 1. (1..7)
+// %U :: Week number of the current year, starting with the first Sunday as
+//       the first day of the first week (00..53)
+// %v :: VMS date (%e-%b-%Y)
+// %V :: Week number of year according to ISO 8601 (01..53)
+// %W :: Week  number  of the current year, starting with the first Monday
+//       as the first day of the first week (00..53)
+// %w :: Day of the week (Sunday is 0, 0..6)
+// %x :: Preferred representation for the date alone, no time
+// %X :: Preferred representation for the time alone, no date
+// %y :: Year without a century (00..99)
+// %Y :: Year which may include century, if provided
+// %z :: Time zone as  hour offset from UTC (e.g. +0900)
+// %Z :: Time zone name
+// %% :: Literal "%" character
+// %+ :: date(1) (%a %b %e %H:%M:%S %Z %Y)
+//
+// You must require 'time' to use this method.
+pub fn strptime_with_custom_time_and_year(
+    date: &str,
+    format: &str,
+    now: Option<chrono::DateTime<FixedOffset>>,
+    year_fn: Option<fn(i32) -> i32>
+)
+-> crate::Result<DateTime<FixedOffset>>
+{
+    let d = crate::date_parser::date_strptime(date, format)
+        .ok_or_else(|| ParseError::invalid_format(date, format))?;
+    let local = LocalZone::system();
+    let now = now.unwrap_or_else(|| local.now());
+
+    if let Some(seconds) = d.seconds {
+        // +seconds+ carries at most millisecond precision (from %Q), so the
+        // fraction is rounded to that to drop floating point noise.
+        let mut nanos = (seconds.fract() * 1_000.0).round() as i64 * 1_000_000;
+        if let Some(frac) = d.sec_fraction {
+            let frac_nanos = (frac * 1_000_000_000.0).round() as i64;
+            nanos += if seconds < 0.0 { -frac_nanos } else { frac_nanos };
+        }
+        let t = chrono::DateTime::from_timestamp(seconds.trunc() as i64, 0)
+            .and_then(|t| t.checked_add_signed(chrono::Duration::nanoseconds(nanos)))
+            .ok_or_else(|| ParseError::out_of_range(ErrorKind::FieldOutOfRange, "seconds", seconds, None).with_input(date))?
+            .fixed_offset();
+        return match &d.zone {
+            Some(zone) => force_zone(t, zone, None, &local),
+            None => to_local_time(t, None, &local),
+        }.map_err(|err| err.with_input(date));
+    }
+
+    let mut year = d.year;
+    if let (Some(y), Some(func)) = (year, year_fn) {
+        year = Some(func(y));
+    }
+    make_week_time(date, &d, year, now, &local)
+}
+
+pub fn strptime(date: &str, format: &str) -> crate::Result<DateTime<FixedOffset>>
+{
+    strptime_with_custom_time_and_year(date, format, None, None)
+}
+
+// Parses +date+ as a dateTime defined by ISO 8601, accepting only the
+// calendar, ordinal and week date forms with an optional time and offset.
+// Missing upper components are supplied from +now+ as +parse+ does.
+//
+// ArgumentError is raised if +date+ is not in one of those forms.
+pub fn iso8601_with_custom_time(date: &str, now: Option<chrono::DateTime<FixedOffset>>) -> crate::Result<DateTime<FixedOffset>>
+{
+    let d = crate::date_parser::iso8601(date)
+        .ok_or_else(|| ParseError::invalid_format(date, "iso8601"))?;
+    let local = LocalZone::system();
+    let now = now.unwrap_or_else(|| local.now());
+    make_week_time(date, &d, d.year, now, &local)
+}
+
+pub fn iso8601(date: &str) -> crate::Result<DateTime<FixedOffset>>
+{
+    iso8601_with_custom_time(date, None)
+}
+
+// Parses +date+ as a date-time defined by RFC 3339. The date, the time
+// including seconds and the offset are all mandatory.
+//
+// ArgumentError is raised if +date+ is not compliant with RFC 3339 or
+// the Time class cannot represent specified date.
+pub fn rfc3339(date: &str) -> crate::Result<DateTime<FixedOffset>>
+{
+    let d = crate::date_parser::rfc3339(date)
+        .ok_or_else(|| ParseError::invalid_format(date, "rfc3339"))?;
+    make_time(date, d.year, None, d.mon, d.mday, d.hour, d.min, d.sec, d.sec_fraction, d.zone.as_deref(), None, &LocalZone::system())
+}
+
+// Creates a new DateTime object by parsing from a string according to
+// some typical JIS X 0301 formats.
+//
+//     DateTime.jisx0301('H13.02.03T04:05:06+07:00')
+//     #=> #<DateTime: 2001-02-03T04:05:06+07:00 ...>
+//
+// ArgumentError is raised if +date+ is not compliant with JIS X 0301, if
+// the date is outside of the given era or if the Time class cannot
+// represent specified date.
+pub fn jisx0301(date: &str) -> crate::Result<DateTime<FixedOffset>>
+{
+    let d = crate::date_parser::jisx0301(date)
+        .ok_or_else(|| ParseError::invalid_format(date, "jisx0301"))?;
+    make_time(date, d.year, None, d.mon, d.mday, d.hour, d.min, d.sec, d.sec_fraction, d.zone.as_deref(), None, &LocalZone::system())
+}
+
+// Parses +date+ as an RFC 9557 timestamp, an RFC 3339 date-time followed
+// by optional [time-zone] and [key=value] suffixes, and converts it to a
+// Time object with the offset of the RFC 3339 part.
+//
+//     rfc9557("2024-10-18T09:30:00+09:00[Asia/Tokyo][u-ca=japanese]")
+//     #=> 2024-10-18 09:30:00 +0900
+//
+// ArgumentError is raised if +date+ is not compliant with RFC 9557, if a
+// critical suffix is not understood or the critical time zone disagrees
+// with the offset, or if the Time class cannot represent specified date.
+pub fn rfc9557(date: &str) -> crate::Result<DateTime<FixedOffset>>
+{
+    let d = crate::date_parser::rfc9557(date)
+        .ok_or_else(|| ParseError::invalid_format(date, "rfc9557"))?;
+    make_time(date, d.year, None, d.mon, d.mday, d.hour, d.min, d.sec, d.sec_fraction, d.zone.as_deref(), None, &LocalZone::system())
+}
+
+// Parses +date+ as date-time defined by RFC 2822 and converts it to a Time
+// object.  The format is identical to the date format defined by RFC 822 and
+// updated by RFC 1123.
+//
+// ArgumentError is raised if +date+ is not compliant with RFC 2822
+// or if the Time class cannot represent specified date.
+//
+// See #rfc2822 for more information on this format.
+//
+//     require 'time'
+//
+//     Time.rfc2822("Wed, 05 Oct 2011 22:26:12 -0400")
+//     #=> 2010-10-05 22:26:12 -0400
+//
+// You must require 'time' to use this method.
+pub fn rfc2822(date: &str) -> crate::Result<DateTime<FixedOffset>>
+{
+    let d = crate::date_parser::rfc2822(date)
+        .ok_or_else(|| ParseError::invalid_format(date, "rfc2822"))?;
+    make_time(date, d.year, None, d.mon, d.mday, d.hour, d.min, Some(d.sec.unwrap_or(0)), None, d.zone.as_deref(), None, &LocalZone::system())
+}
+
+// Parses +date+ as an HTTP-date defined by RFC 2616 and converts it to a
+// Time object.
+//
+// ArgumentError is raised if +date+ is not compliant with RFC 2616 or if
+// the Time class cannot represent specified date.
+//
+// See #httpdate for more information on this format.
+//
+//     require 'time'
+//
+//     Time.httpdate("Thu, 06 Oct 2011 02:26:12 GMT")
+//     #=> 2011-10-06 02:26:12 UTC
+//
+// You must require 'time' to use this method.
+pub fn httpdate(date: &str) -> crate::Result<DateTime<FixedOffset>>
+{
+    let d = match super::strict::httpdate_type2(date) {
+        // The two digit years of the obsolete RFC 850 form are completed
+        // as RFC 2822 does, unlike Date._httpdate.
+        Some(d) => crate::DateTime { year: d.year.map(|x| super::strict::comp_year50(x % 100)), ..d },
+        None => crate::date_parser::httpdate(date)
+            .ok_or_else(|| ParseError::invalid_format(date, "httpdate"))?,
+    };
+    // asctime() dates have no zone and are in UTC as well.
+    let zone = d.zone.as_deref().unwrap_or("UTC");
+    make_time(date, d.year, None, d.mon, d.mday, d.hour, d.min, d.sec, None, Some(zone), None, &LocalZone::system())
+        .map(|x| x.to_utc().fixed_offset())
+}
+
+// Parses +date+ as a dateTime defined by the XML Schema and converts it to
+// a Time object.  The format is a restricted version of the format defined
+// by ISO 8601.
+//
+// ArgumentError is raised if +date+ is not compliant with the format or if
+// the Time class cannot represent specified date.
+//
+// See #xmlschema for more information on this format.
+//
+//     require 'time'
+//
+//     Time.xmlschema("2011-10-05T22:26:12-04:00")
+//     #=> 2011-10-05 22:26:12-04:00
+//
+// You must require 'time' to use this method.
+pub fn xmlschema(date: &str) -> crate::Result<DateTime<FixedOffset>>
+{
+    lazy_static! {
+        static ref XMLSCHEMA_REGEX: Regex = RegexBuilder::new(r"
+            \A\s*
+            (-?[0-9]+)-([0-9][0-9])-([0-9][0-9])
+            (?:T
+            ([0-9][0-9]):([0-9][0-9]):([0-9][0-9])
+            (\.[0-9]+)?)?
+            (Z|[+-][0-9][0-9](?::?[0-9][0-9])?)?
+            \s*\z
+        ")
+        .case_insensitive(true)
+        .ignore_whitespace(true)
+        .build()
+        .unwrap();
+    }
+    let invalid = || ParseError::invalid_format(date, "xmlschema");
+    let caps = XMLSCHEMA_REGEX.captures(date).ok_or_else(invalid)?;
+    let num = |i: usize| caps.get(i).and_then(|x| x.as_str().parse::<u32>().ok());
+
+    let year = caps.get(1)
+        .map(|x| x.as_str())
+        .map(|x| x.parse::<i32>().map_err(|_| ParseError::out_of_range(ErrorKind::UnrepresentableYear, "year", x, None).with_input(date)))
+        .ok_or_else(invalid)??;
+    let sec_fraction = caps.get(7).and_then(|x| format!("0{}", x.as_str()).parse::<f64>().ok());
+    let zone = caps.get(8).map(|x| x.as_str());
+    make_time(date, Some(year), None, num(2), num(3), num(4), num(5), num(6), sec_fraction, zone, None, &LocalZone::system())
+}
+
 pub fn local(year: Option<i32>, month: Option<u32>, day: Option<u32>, hour: Option<u32>, min: Option<u32>, sec: Option<u32>, tz: Option<&str>) -> crate::Result<DateTime<FixedOffset>> {
     let year = year.unwrap_or_else(||{
         chrono::Local::now().year()
     });
+    let (year, month, day) = validated_ymd(year, field_i32("mon", month.unwrap_or(1))?, field_i32("mday", day.unwrap_or(1))?)?;
+    let (hour, min, sec) = validated_hms(field_i32("hour", hour.unwrap_or(0))?, field_i32("min", min.unwrap_or(0))?, field_i32("sec", sec.unwrap_or(0))?)?;
 
     let dt = if let Some(tz_val) = tz {
-        // if let Some(val) = chrono_tz::Tz::parse(tz_val) {
-
-        // }
-
-        let fixed_offset = FixedOffset::from_str(tz_val).unwrap();
-        fixed_offset.with_ymd_and_hms(
-            year,
-            month.unwrap_or(1),
-            day.unwrap_or(1),
-            hour.unwrap_or(0),
-            min.unwrap_or(0),
-            sec.unwrap_or(0),
-            ).earliest()
+        let fixed_offset = FixedOffset::from_str(tz_val)
+            .map_err(|_| ParseError::new(ErrorKind::UnknownZone).with_detail(tz_val))?;
+        fixed_offset.with_ymd_and_hms(year, month, day, hour, min, sec).earliest()
     } else {
-        let tz_str = iana_time_zone::get_timezone().unwrap();
-        let tz: chrono_tz::Tz = tz_str.parse().unwrap();
-        tz.with_ymd_and_hms(
-            year,
-            month.unwrap_or(1),
-            day.unwrap_or(1),
-            hour.unwrap_or(0),
-            min.unwrap_or(0),
-            sec.unwrap_or(0),
-            ).earliest().map(|x| x.fixed_offset())
+        LocalZone::system().tz.with_ymd_and_hms(year, month, day, hour, min, sec).earliest().map(|x| x.fixed_offset())
     };
-    if let Some(val) = dt {
-        Ok(val)
-    } else {
-        Err(crate::ParseError::DateError())
-    }
+    dt.ok_or_else(|| unrepresentable_year(year))
 }
 
 #[allow(unused_macros)]
@@ -716,6 +1328,45 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_errors() {
+        let err = parse("no date here").unwrap_err();
+        assert_eq!((err.kind(), err.input()), (ErrorKind::NoDateFound, Some("no date here")));
+        assert_eq!(err.to_string(), "no time information in `no date here'");
+
+        let err = parse("Order #12 shipped 2024-13-01").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::FieldOutOfRange);
+        assert_eq!(err.field(), Some("mon"));
+        assert_eq!(err.value(), Some("13"));
+        assert_eq!(err.allowed(), Some(1..=12));
+        assert_eq!(err.span(), Some(18..28));
+        assert_eq!(err.to_string(), "month value 13 out of range 1..=12 at 18..28 in `Order #12 shipped 2024-13-01'");
+
+        let err = parse("2024-02-30").unwrap_err();
+        assert_eq!((err.kind(), err.field(), err.allowed()), (ErrorKind::InvalidDayOfMonth, Some("mday"), Some(1..=29)));
+        let err = parse("2023-366").unwrap_err();
+        assert_eq!((err.kind(), err.field(), err.allowed()), (ErrorKind::InvalidYday, Some("yday"), Some(1..=365)));
+        let err = parse("10:61").unwrap_err();
+        assert_eq!((err.kind(), err.field(), err.span()), (ErrorKind::FieldOutOfRange, Some("min"), Some(0..5)));
+
+        let err = strptime("2001-W53", "%G-W%V").unwrap_err();
+        assert_eq!((err.kind(), err.field(), err.allowed()), (ErrorKind::FieldOutOfRange, Some("cweek"), Some(1..=52)));
+        let err = strptime("+999999-01-01", "%Y-%m-%d").unwrap_err();
+        assert_eq!((err.kind(), err.field()), (ErrorKind::UnrepresentableYear, Some("year")));
+
+        let err = iso8601("Feb 3 2001").unwrap_err();
+        assert_eq!((err.kind(), err.detail()), (ErrorKind::InvalidFormat, Some("iso8601")));
+        assert_eq!(err.to_string(), "invalid date or format - `Feb 3 2001' `iso8601'");
+
+        let err = parse_in_zone_with_dst_policy("2024-03-10 02:30", chrono_tz::America::New_York, DstPolicy::Reject).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::SkippedLocalTime);
+        assert_eq!(err.span(), Some(11..16));
+        assert_eq!(err.to_string(), "local time 2024-03-10 02:30:00 does not exist in America/New_York at 11..16 in `2024-03-10 02:30'");
+
+        let err = local(Some(2001), None, None, None, None, None, Some("Mars/Olympus")).unwrap_err();
+        assert_eq!((err.kind(), err.detail()), (ErrorKind::UnknownZone, Some("Mars/Olympus")));
+    }
+
     #[test]
     fn test_completion_with_different_timezone() {
         let now_local: DateTime<FixedOffset> = local_time!(2001,2,3,0,0,0,"+09:00");
@@ -756,7 +1407,7 @@ mod test {
 
     #[test]
     fn test_parse_offset_hour_minute_second() {
-        let now_local: DateTime<FixedOffset> = local_time!(-214748);
+        let now_local: DateTime<FixedOffset> = utc_time!(-1199,2,15,14,13,20);
         assert_eq!(parse("1200-02-15 BC 14:13:20-00").unwrap(), now_local);
         assert_eq!(parse("1200-02-15 BC 14:13:20-00:00").unwrap(), now_local);
         assert_eq!(parse("1200-02-15 BC 14:13:20-00:00:00").unwrap(), now_local);
@@ -767,4 +1418,366 @@ mod test {
         assert!(parse("26-05").is_ok());
     }
 
+    #[test]
+    fn test_strptime() {
+        assert_eq!(utc_time!(2001,2,3,4,5,6), strptime("2001-02-03T04:05:06Z", "%Y-%m-%dT%H:%M:%S%z").unwrap());
+        assert_eq!(local_time!(2001,2,3,4,5,6,"+09:00"), strptime("2001-02-03 04:05:06 +0900", "%F %T %z").unwrap());
+        assert_eq!(local_time!(2001,2,3), strptime("01/02/03", "%y/%m/%d").unwrap());
+        assert_eq!(local_time!(2003,1,2), strptime("01/02/03", "%m/%d/%y").unwrap());
+        assert!(strptime("01/02/03", "%Y-%m-%d").is_err());
+        assert!(strptime("2001-02-30", "%Y-%m-%d").is_err());
+    }
+
+    #[test]
+    fn test_strptime_completion() {
+        let now = local_time!(2001,11,29, 21,26,35);
+        assert_eq!(local_time!(2001,11,29,10,22), strptime_with_custom_time_and_year("10:22", "%H:%M", Some(now), None).unwrap());
+        assert_eq!(local_time!(2001,7,23), strptime_with_custom_time_and_year("7/23", "%m/%d", Some(now), None).unwrap());
+        assert_eq!(local_time!(2001,2,3), strptime_with_custom_time_and_year("1-02-03", "%Y-%m-%d", Some(now), Some(|y| y + 2000)).unwrap());
+    }
+
+    #[test]
+    fn test_strptime_seconds() {
+        assert_eq!(utc_time!(2001,2,3,4,5,6), strptime("981173106", "%s").unwrap());
+        assert_eq!(utc_time!(2001,2,3,4,5,6).to_utc(), strptime("981173106 +0900", "%s %z").unwrap().to_utc());
+        assert_eq!(9 * 3600, strptime("981173106 +0900", "%s %z").unwrap().offset().local_minus_utc());
+        assert_eq!(123_000_000, strptime("981173106123", "%Q").unwrap().nanosecond());
+        assert_eq!(utc_time!(1969,12,31,23,59,58).timestamp_nanos_opt().unwrap() + 500_000_000, strptime("-1.5", "%s.%N").unwrap().timestamp_nanos_opt().unwrap());
+    }
+
+    #[test]
+    fn test_iso8601() {
+        let now = local_time!(2001,11,29, 21,26,35);
+        assert_eq!(utc_time!(2001,2,3,4,5,6), iso8601("2001-02-03T04:05:06Z").unwrap());
+        assert_eq!(local_time!(2001,2,3,4,5,6,"+07:00"), iso8601("20010203T040506+0700").unwrap());
+        assert_eq!(local_time!(2001,2,3), iso8601_with_custom_time("2001-034", Some(now)).unwrap());
+        assert_eq!(local_time!(2001,2,3), iso8601_with_custom_time("2001-W05-6", Some(now)).unwrap());
+        assert_eq!(local_time!(2001,11,29,4,5), iso8601_with_custom_time("04:05", Some(now)).unwrap());
+        assert!(iso8601("Feb 3 2001").is_err());
+        assert!(iso8601("2001-02-30").is_err());
+        assert!(matches!(iso8601("2024-01-02T10:00:00.٥Z").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+    }
+
+    #[test]
+    fn test_rfc3339() {
+        assert_eq!(local_time!(2001,2,3,4,5,6,"+07:00"), rfc3339("2001-02-03T04:05:06+07:00").unwrap());
+        assert_eq!(utc_time!(2001,2,3,4,5,6), rfc3339("2001-02-03 04:05:06Z").unwrap());
+        assert_eq!(500_000_000, rfc3339("2001-02-03T04:05:06.5-00:00").unwrap().nanosecond());
+        assert!(matches!(rfc3339("2001-02-03T04:05:06").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(rfc3339("2001-02-03").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(rfc3339("2001-02-30T04:05:06Z").map_err(|e| e.kind()), Err(ErrorKind::InvalidDayOfMonth)));
+    }
+
+    #[test]
+    fn test_xmlschema() {
+        assert_eq!(local_time!(2011,10,5,22,26,12,"-04:00"), xmlschema("2011-10-05T22:26:12-04:00").unwrap());
+        assert_eq!(local_time!(2011,10,5,22,26,12,"-04:00"), xmlschema("2011-10-05T22:26:12-0400").unwrap());
+        assert_eq!(utc_time!(2011,10,5,22,26,12), xmlschema("2011-10-05T22:26:12Z").unwrap());
+        assert_eq!(local_time!(2011,10,5,22,26,12), xmlschema("2011-10-05T22:26:12").unwrap());
+        assert_eq!(250_000_000, xmlschema("2011-10-05T22:26:12.25Z").unwrap().nanosecond());
+        assert_eq!(local_time!(2011,10,5), xmlschema("2011-10-05").unwrap());
+        assert_eq!(utc_time!(2011,10,5), xmlschema("2011-10-05Z").unwrap());
+        assert!(matches!(xmlschema("٢٠١١-10-05T22:26:12Z").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(xmlschema("2011-10-05 22:26:12Z").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(xmlschema("2011-13-05T22:26:12Z").map_err(|e| e.kind()), Err(ErrorKind::FieldOutOfRange)));
+    }
+
+    #[test]
+    fn test_tz_variable_zone() {
+        use crate::date_parser::tz_variable_zone;
+        assert_eq!(Some(chrono_tz::America::New_York), tz_variable_zone("America/New_York"));
+        assert_eq!(Some(chrono_tz::America::New_York), tz_variable_zone(":America/New_York"));
+        assert_eq!(Some(chrono_tz::Europe::Berlin), tz_variable_zone("/usr/share/zoneinfo/Europe/Berlin"));
+        assert_eq!(Some(chrono_tz::UTC), tz_variable_zone(""));
+        assert_eq!(None, tz_variable_zone("Not/AZone"));
+    }
+
+    #[test]
+    fn test_parse_iana_zone() {
+        assert_eq!(local_time!(2024,6,1,10,0,0,"+02:00"), parse("2024-06-01 10:00 Europe/Berlin").unwrap());
+        assert_eq!(local_time!(2024,1,1,10,0,0,"+01:00"), parse("2024-01-01 10:00 Europe/Berlin").unwrap());
+        assert_eq!(local_time!(2024,6,1,10,0,0,"-03:00"), parse("2024-06-01T10:00[America/Sao_Paulo]").unwrap());
+        // Wall clock times in a DST gap move forward.
+        assert_eq!(local_time!(2024,3,10,3,30,0,"-04:00"), parse("2024-03-10 02:30 America/New_York").unwrap());
+        assert_eq!(local_time!(2024,11,3,1,30,0,"-04:00"), parse("2024-11-03 01:30 America/New_York").unwrap());
+        assert!(matches!(parse_in_zone_with_dst_policy("2024-03-10 02:30 America/New_York", chrono_tz::UTC, DstPolicy::Reject).map_err(|e| e.kind()), Err(ErrorKind::SkippedLocalTime)));
+
+        // Missing components come from now in the named zone.
+        let now = local_time!(2024,1,15,23,0,0,"+00:00");
+        assert_eq!(local_time!(2024,1,16,10,0,0,"+01:00"), parse_with_custom_time_and_year("10:00 Europe/Berlin", Some(now), None).unwrap());
+        let now = local_time!(2024,7,15,12,0,0,"+00:00");
+        assert_eq!(local_time!(2024,7,15,10,0,0,"+09:00"), parse_with_custom_time_and_year("10:00 Asia/Tokyo", Some(now), None).unwrap());
+    }
+
+    #[test]
+    fn test_parse_with_year_pivot() {
+        let now = local_time!(2024,10,18,12,0,0,"+00:00");
+        assert_eq!(utc_time!(2075,12,31), parse_with_year_pivot("Dec 31 75 UTC", Some(now), YearPivot::ClosestTo(2030)).unwrap());
+        assert_eq!(utc_time!(1975,12,31), parse_with_year_pivot("Dec 31 75 UTC", Some(now), YearPivot::ClosestTo(2000)).unwrap());
+        assert_eq!(utc_time!(1975,12,31), parse_with_year_pivot("Dec 31 75 UTC", Some(now), YearPivot::ClosestToNow).unwrap());
+        assert_eq!(utc_time!(2073,12,31), parse_with_year_pivot("Dec 31 73 UTC", Some(now), YearPivot::ClosestToNow).unwrap());
+        assert_eq!(utc_time!(1968,1,1), parse_with_year_pivot("68-01-01 UTC", Some(now), YearPivot::Fixed(1950)).unwrap());
+        assert_eq!(utc_time!(2068,1,1), parse_with_year_pivot("68-01-01 UTC", Some(now), YearPivot::default()).unwrap());
+        // Four digit years are left alone.
+        assert_eq!(utc_time!(1875,12,31), parse_with_year_pivot("Dec 31 1875 UTC", Some(now), YearPivot::ClosestToNow).unwrap());
+        // Week based years too.
+        assert_eq!(2024, crate::date_parser::date_parse_with_year_pivot("24-w10-3", YearPivot::Fixed(2000)).cwyear.unwrap());
+    }
+
+    #[test]
+    fn test_parse_with_options() {
+        let now = local_time!(2024,10,18,12,0,0,"+00:00");
+        let options = ParseOptions::new().now(now);
+        assert_eq!(utc_time!(2024,10,18,16,30), parse_with_options("16:30", &options).unwrap());
+        assert_eq!(utc_time!(2024,7,23), parse_with_options("7/23", &options).unwrap());
+
+        // The year block can capture state.
+        let century = 1800;
+        let options = ParseOptions::new().now(now).year_fn(move |y| if y < 100 { y + century } else { y });
+        assert_eq!(utc_time!(1801,10,31), parse_with_options("01-10-31", &options).unwrap());
+        assert_eq!(utc_time!(2001,10,31), parse_with_options("2001-10-31", &options).unwrap());
+
+        let options = ParseOptions::new().now(now).year_pivot(YearPivot::ClosestTo(2030));
+        assert_eq!(utc_time!(2075,12,31), parse_with_options("Dec 31 75", &options).unwrap());
+
+        let options = ParseOptions::new().now(now).zone(chrono_tz::Asia::Tokyo);
+        assert_eq!(local_time!(2024,10,18,10,0,0,"+09:00"), parse_with_options("10:00", &options).unwrap());
+        assert_eq!(local_time!(2024,10,19,0,0,0,"+09:00"), parse_with_options("Oct 19", &options).unwrap());
+
+        let options = ParseOptions::new().now(now).date_order(crate::DateOrder::Dmy);
+        assert_eq!(utc_time!(2024,4,3), parse_with_options("03/04/24", &options).unwrap());
+        let options = ParseOptions::new().now(now).date_order(crate::DateOrder::Mdy);
+        assert_eq!(utc_time!(2024,3,4), parse_with_options("03/04/2024", &options).unwrap());
+        assert!(matches!(parse_with_options("13/04/2024", &options).map_err(|e| e.kind()), Err(ErrorKind::FieldOutOfRange)));
+
+        let options = ParseOptions::new().zone(chrono_tz::America::New_York).dst_policy(DstPolicy::Reject);
+        assert!(matches!(parse_with_options("2024-03-10 02:30", &options).map_err(|e| e.kind()), Err(ErrorKind::SkippedLocalTime)));
+
+        // Options can be shared between threads.
+        let options = std::sync::Arc::new(ParseOptions::new().now(now).year_fn(move |y| y + century));
+        let handle = {
+            let options = options.clone();
+            std::thread::spawn(move || parse_with_options("01-10-31", &options).unwrap())
+        };
+        assert_eq!(utc_time!(1801,10,31), handle.join().unwrap());
+    }
+
+    #[test]
+    fn test_parse_relative() {
+        // a Thursday
+        let now = local_time!(2001,11,29, 21,26,35,"+00:00");
+        let parse = |x| parse_with_options(x, &ParseOptions::new().now(now)).unwrap();
+        assert_eq!(utc_time!(2001,11,29), parse("today"));
+        assert_eq!(utc_time!(2001,11,30), parse("Tomorrow"));
+        assert_eq!(utc_time!(2001,11,28), parse("yesterday"));
+        assert_eq!(utc_time!(2001,11,30,10,0), parse("tomorrow at 10:00"));
+        assert_eq!(local_time!(2001,11,30, 10,0,0,"-05:00"), parse("tomorrow 10am EST"));
+        assert_eq!(now, parse("now"));
+        assert_eq!(utc_time!(2001,11,26,21,26,35), parse("3 days ago"));
+        assert_eq!(utc_time!(2001,11,29,23,26,35), parse("in 2 hours"));
+        assert_eq!(utc_time!(2001,11,29,20,26,35), parse("an hour ago"));
+        assert_eq!(utc_time!(2001,12,13,21,26,35), parse("2 weeks from now"));
+        assert_eq!(utc_time!(2001,11,26,9,0), parse("3 days ago at 9:00"));
+        assert_eq!(utc_time!(2002,2,28,21,26,35), parse("in 3 months"));
+        assert_eq!(utc_time!(2001,11,30), parse("next Friday"));
+        assert_eq!(utc_time!(2001,12,6), parse("next thursday"));
+        assert_eq!(utc_time!(2001,11,23), parse("last Fri"));
+        assert_eq!(utc_time!(2001,11,25,18,0), parse("this Sunday 6pm"));
+        assert_eq!(utc_time!(2001,12,2), parse("next week"));
+        assert_eq!(utc_time!(2001,10,1), parse("last month"));
+        assert_eq!(utc_time!(2001,11,1), parse("this month"));
+        assert_eq!(utc_time!(2002,1,1), parse("next year"));
+        assert_eq!(utc_time!(2001,12,1), parse("this weekend"));
+        assert_eq!(utc_time!(2001,11,24), parse("last weekend"));
+        // A date in the input wins.
+        assert_eq!(utc_time!(2001,1,5), parse("today is 2001-01-05"));
+
+        assert_eq!(local_time!(2001,11,29, 22,56,35,"+00:00"), parse("now + 1h30m"));
+        assert_eq!(local_time!(2001,11,28, 21,26,35,"+00:00"), parse("now - P1D"));
+        assert_eq!(local_time!(2001,11,30, 23,26,35,"+00:00"), parse("in 1 day 02:00:00"));
+        assert_eq!(local_time!(2001,11,29, 19,56,35,"+00:00"), parse("PT90M ago"));
+        assert!(matches!(
+            parse_with_options("now + 3 fortnights", &ParseOptions::new().now(now)).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidDuration)
+        ));
+    }
+
+    #[test]
+    fn test_parse_relative_in_period() {
+        // a Thursday
+        let now = local_time!(2001,11,29, 21,26,35,"+00:00");
+        let parse = |x| parse_with_options(x, &ParseOptions::new().now(now));
+        assert_eq!(utc_time!(2025,3,11), parse("second Tuesday of March 2025").unwrap());
+        assert_eq!(utc_time!(2001,1,17), parse("3rd Wednesday in Jan").unwrap());
+        assert_eq!(utc_time!(2001,11,30), parse("last Friday of the month").unwrap());
+        assert_eq!(utc_time!(2001,12,3,9,0), parse("first Monday of next month at 9:00").unwrap());
+        assert_eq!(utc_time!(2001,7,2), parse("first business day of Q3").unwrap());
+        assert_eq!(utc_time!(2001,10,31), parse("last business day of last month").unwrap());
+        assert_eq!(utc_time!(2024,2,29), parse("last day of February 2024").unwrap());
+        assert_eq!(utc_time!(2001,11,30), parse("end of month").unwrap());
+        assert_eq!(utc_time!(2001,12,1), parse("start of next month").unwrap());
+        assert_eq!(utc_time!(2001,12,31), parse("end of the quarter").unwrap());
+        assert_eq!(utc_time!(2001,12,1), parse("end of week").unwrap());
+        assert_eq!(utc_time!(2025,12,31), parse("end of 2025").unwrap());
+
+        let err = parse("fifth Monday of Feb 2025").unwrap_err();
+        assert_eq!((err.kind(), err.allowed(), err.span()), (ErrorKind::FieldOutOfRange, Some(1..=4), Some(0..24)));
+    }
+
+    #[test]
+    fn test_parse_weekday_policy() {
+        let header = "Sat, 01 Jun 2000 10:00:00 +0000";
+        let parse = |policy| parse_with_options(header, &ParseOptions::new().weekday_policy(policy));
+        assert_eq!(utc_time!(2000,6,1,10,0), parse(WeekdayPolicy::Ignore).unwrap());
+        assert_eq!(utc_time!(2000,6,3,10,0), parse(WeekdayPolicy::PreferWeekday).unwrap());
+        let err = parse(WeekdayPolicy::Reject).unwrap_err();
+        assert_eq!((err.kind(), err.field(), err.span()), (ErrorKind::WeekdayMismatch, Some("wday"), Some(0..4)));
+        assert_eq!(err.to_string(), "day of the week Sat doesn't match 2000-06-01 (Thu) at 0..4 in `Sat, 01 Jun 2000 10:00:00 +0000'");
+        // The nearest day, which may be in the week before.
+        let options = ParseOptions::new().weekday_policy(WeekdayPolicy::PreferWeekday);
+        assert_eq!(utc_time!(2000,5,29), parse_with_options("Mon, 01 Jun 2000 UTC", &options).unwrap());
+        assert_eq!(utc_time!(2000,6,1), parse_with_options("Thu, 01 Jun 2000 UTC", &options).unwrap());
+
+        let warnings = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = warnings.clone();
+        let options = ParseOptions::new()
+            .weekday_policy(WeekdayPolicy::Warn)
+            .on_warning(move |err| sink.lock().unwrap().push(err.clone()));
+        assert_eq!(utc_time!(2000,6,1,10,0), parse_with_options(header, &options).unwrap());
+        assert_eq!(utc_time!(2000,6,1,10,0), parse_with_options("Thu, 01 Jun 2000 10:00:00 +0000", &options).unwrap());
+        let warnings = warnings.lock().unwrap();
+        assert_eq!(1, warnings.len());
+        assert_eq!((warnings[0].kind(), warnings[0].input()), (ErrorKind::WeekdayMismatch, Some(header)));
+    }
+
+    #[test]
+    fn test_parse_in_zone() {
+        use chrono_tz::America::New_York;
+        use chrono_tz::Europe::Berlin;
+        let ny = |y, m, d, h, mi| New_York.with_ymd_and_hms(y, m, d, h, mi, 0).single().unwrap();
+
+        assert_eq!(ny(2024,1,15,10,0), parse_in_zone("2024-01-15 10:00", New_York).unwrap());
+        assert_eq!(ny(2024,7,1,10,0), parse_in_zone("2024-07-01 10:00", New_York).unwrap());
+        assert_eq!(-5 * 3600, parse_in_zone("2024-01-15 10:00", New_York).unwrap().fixed_offset().offset().local_minus_utc());
+        assert_eq!(-4 * 3600, parse_in_zone("2024-07-01 10:00", New_York).unwrap().fixed_offset().offset().local_minus_utc());
+
+        // Inputs with an offset keep their instant.
+        assert_eq!(ny(2024,7,1,6,0), parse_in_zone("2024-07-01 10:00 UTC", New_York).unwrap());
+        assert_eq!(ny(2024,7,1,4,0), parse_in_zone("2024-07-01T10:00:00+02:00", New_York).unwrap());
+        assert_eq!(Berlin.with_ymd_and_hms(2024,7,1,16,0,0).unwrap(), parse_in_zone("2024-07-01 10:00 EDT", Berlin).unwrap());
+    }
+
+    #[test]
+    fn test_parse_in_zone_dst() {
+        use chrono_tz::America::New_York;
+        let utc = |y, m, d, h, mi| chrono::Utc.with_ymd_and_hms(y, m, d, h, mi, 0).unwrap();
+
+        // 2024-03-10 02:30 doesn't exist in New York.
+        let gap = "2024-03-10 02:30";
+        assert_eq!(utc(2024,3,10,7,30), parse_in_zone(gap, New_York).unwrap());
+        assert_eq!(utc(2024,3,10,7,30), parse_in_zone_with_dst_policy(gap, New_York, DstPolicy::Later).unwrap());
+        assert_eq!(utc(2024,3,10,6,30), parse_in_zone_with_dst_policy(gap, New_York, DstPolicy::Earlier).unwrap());
+        assert_eq!("2024-03-10 03:30:00 EDT", parse_in_zone(gap, New_York).unwrap().to_string());
+        assert_eq!("2024-03-10 01:30:00 EST", parse_in_zone_with_dst_policy(gap, New_York, DstPolicy::Earlier).unwrap().to_string());
+        assert!(matches!(parse_in_zone_with_dst_policy(gap, New_York, DstPolicy::Reject).map_err(|e| e.kind()), Err(ErrorKind::SkippedLocalTime)));
+
+        // 2024-11-03 01:30 happens twice in New York.
+        let overlap = "2024-11-03 01:30";
+        assert_eq!(utc(2024,11,3,5,30), parse_in_zone(overlap, New_York).unwrap());
+        assert_eq!(utc(2024,11,3,5,30), parse_in_zone_with_dst_policy(overlap, New_York, DstPolicy::Earlier).unwrap());
+        assert_eq!(utc(2024,11,3,6,30), parse_in_zone_with_dst_policy(overlap, New_York, DstPolicy::Later).unwrap());
+        assert!(matches!(parse_in_zone_with_dst_policy(overlap, New_York, DstPolicy::Reject).map_err(|e| e.kind()), Err(ErrorKind::AmbiguousLocalTime)));
+
+        // An explicit offset is never ambiguous.
+        assert_eq!(utc(2024,11,3,6,30), parse_in_zone_with_dst_policy("2024-11-03 01:30 EST", New_York, DstPolicy::Reject).unwrap());
+        assert_eq!(utc(2024,3,10,7,30), parse_in_zone_with_dst_policy("2024-03-10 02:30 -0500", New_York, DstPolicy::Reject).unwrap());
+    }
+
+    #[test]
+    fn test_jisx0301() {
+        assert_eq!(local_time!(2001,2,3,4,5,6,"+07:00"), jisx0301("H13.02.03T04:05:06+07:00").unwrap());
+        assert_eq!(utc_time!(2024,10,18,0,0,0), jisx0301("R06.10.18T00:00:00Z").unwrap());
+        assert_eq!(utc_time!(2019,4,30,23,59,59), jisx0301("平成31.04.30T23:59:59Z").unwrap());
+        assert_eq!(utc_time!(2019,5,1,0,0,0), jisx0301("令和01.05.01T00:00:00Z").unwrap());
+
+        assert!(matches!(jisx0301("H31.05.01T00:00:00Z").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(jisx0301("H32.01.01").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(jisx0301("R1.5.1").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+    }
+
+    #[test]
+    fn test_rfc9557() {
+        assert_eq!(local_time!(2024,10,18,9,30,0,"+09:00"), rfc9557("2024-10-18T09:30:00+09:00[Asia/Tokyo][u-ca=japanese]").unwrap());
+        assert_eq!(local_time!(2024,7,1,10,0,0,"-04:00"), rfc9557("2024-07-01T10:00:00-04:00[!America/New_York]").unwrap());
+        assert_eq!(utc_time!(2024,7,1,10,0,0), rfc9557("2024-07-01T10:00:00Z[!America/New_York]").unwrap());
+        assert_eq!(utc_time!(2024,7,1,10,0,0), rfc9557("2024-07-01T10:00:00Z").unwrap());
+
+        assert!(matches!(rfc9557("2024-07-01T10:00:00-05:00[!America/New_York]").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(rfc9557("2024-07-01T10:00:00[America/New_York]").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+    }
+
+    #[test]
+    fn test_rfc2822() {
+        assert_eq!(local_time!(2011,10,5,22,26,12,"-04:00"), rfc2822("Wed, 05 Oct 2011 22:26:12 -0400").unwrap());
+        assert_eq!(utc_time!(1999,8,22,0,45,29), rfc2822("Sun, 22 Aug 99 00:45:29 GMT").unwrap());
+        assert_eq!(utc_time!(2049,8,22,0,45), rfc2822("22 Aug 49 00:45 UT").unwrap());
+        assert_eq!(local_time!(2001,2,3,4,5,6,"-05:00"), rfc2822("Sat, 3 Feb 2001 04:05:06 EST").unwrap());
+        // Military zones keep their original RFC 822 meaning.
+        assert_eq!(local_time!(2001,2,3,4,5,6,"+01:00"), rfc2822("Sat, 3 Feb 2001 04:05:06 A").unwrap());
+        assert_eq!(local_time!(2001,2,3,4,5,6,"-12:00"), rfc2822("Sat, 3 Feb 2001 04:05:06 Y").unwrap());
+
+        assert!(matches!(rfc2822("2001-02-03T04:05:06Z").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(rfc2822("Sat, 3 Feb 2001 04:05:06").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(rfc2822("Sat, 3 Feb 2001 04:05:06 JST").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(rfc2822("Sat, 3 Feb 2001 04:05:06 +0000 garbage").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(rfc2822("Sat, 30 Feb 2001 04:05:06 GMT").map_err(|e| e.kind()), Err(ErrorKind::InvalidDayOfMonth)));
+    }
+
+    #[test]
+    fn test_httpdate() {
+        assert_eq!(utc_time!(2011,10,6,2,26,12), httpdate("Thu, 06 Oct 2011 02:26:12 GMT").unwrap());
+        assert_eq!(utc_time!(1994,11,6,8,49,37), httpdate("Sunday, 06-Nov-94 08:49:37 GMT").unwrap());
+        assert_eq!(utc_time!(2049,11,6,8,49,37), httpdate("Saturday, 06-Nov-49 08:49:37 GMT").unwrap());
+        assert_eq!(utc_time!(1950,11,6,8,49,37), httpdate("Monday, 06-Nov-50 08:49:37 GMT").unwrap());
+        assert_eq!(utc_time!(1994,11,6,8,49,37), httpdate("Sun Nov  6 08:49:37 1994").unwrap());
+        assert_eq!(utc_time!(1994,11,16,8,49,37), httpdate("Wed Nov 16 08:49:37 1994").unwrap());
+        assert_eq!(utc_time!(2011,10,6,2,26,12), httpdate("Thu,  06 Oct 2011 02:26:12 GMT").unwrap());
+        assert_eq!(0, httpdate("Thu, 06 Oct 2011 02:26:12 GMT").unwrap().offset().local_minus_utc());
+
+        assert!(matches!(httpdate("Thu, 06 Oct 2011 02:26:12 +0000").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(httpdate("Thu, 6 Oct 2011 02:26:12 GMT").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(httpdate("Thu, 06 Oct 2011 02:26:12 GMT garbage").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+        assert!(matches!(httpdate("2011-10-06").map_err(|e| e.kind()), Err(ErrorKind::InvalidFormat)));
+    }
+
+    #[test]
+    fn test_strptime_week() {
+        let now = local_time!(2001,11,29, 21,26,35);
+        assert_eq!(local_time!(2001,2,3), strptime_with_custom_time_and_year("2001-W05-6", "%G-W%V-%u", Some(now), None).unwrap());
+        assert_eq!(local_time!(2001,11,26), strptime_with_custom_time_and_year("W48", "W%V", Some(now), None).unwrap());
+        assert_eq!(local_time!(2001,2,3), strptime_with_custom_time_and_year("2001 04 6", "%Y %U %w", Some(now), None).unwrap());
+        assert_eq!(local_time!(2001,2,3), strptime_with_custom_time_and_year("2001 05 6", "%Y %W %w", Some(now), None).unwrap());
+        assert_eq!(local_time!(2001,1,1), strptime_with_custom_time_and_year("2001 01", "%Y %W", Some(now), None).unwrap());
+    }
+
+    #[test]
+    fn test_parse_week() {
+        let now = local_time!(2001,11,29, 21,26,35);
+        let parse = |x| parse_with_custom_time_and_year(x, Some(now), None).unwrap();
+        assert_eq!(local_time!(2024,3,6), parse("2024-W10-3"));
+        assert_eq!(local_time!(2024,3,4), parse("2024-W10"));
+        assert_eq!(local_time!(2001,3,5), parse("W10"));
+        assert_eq!(local_time!(2001,3,7), parse("w10-3"));
+        assert_eq!(local_time!(2001,11,30, 10,0), parse("Fri 10:00"));
+        assert_eq!(local_time!(2001,11,25), parse("Sunday"));
+        assert_eq!(local_time!(2001,12,1), parse("Sat"));
+        assert_eq!(local_time!(2001,11,26), parse("mon"));
+        // a date wins over the day of the week
+        assert_eq!(local_time!(2001,11,7), parse("Fri Nov 7"));
+        assert!(matches!(
+            parse_with_custom_time_and_year("2001-W53", Some(now), None).map_err(|e| e.kind()),
+            Err(ErrorKind::FieldOutOfRange)
+        ));
+    }
+
 }
\ No newline at end of file
@@ -325,4 +325,26 @@ static TIMEZONE_OFFSETS: phf::Map<&'static str, i32> = phf_map! {
 pub fn zone_offset(zone: &str) -> Option<i32> {
     let cleaned = zone.to_lowercase().trim().replace('.', "");
     TIMEZONE_OFFSETS.get(&cleaned).copied()
-}
\ No newline at end of file
+}
+
+// IANA time zone identifiers such as Europe/Berlin. Only the names with a
+// region are looked up, the single word ones (EST, Japan) are left to the
+// abbreviations above.
+pub fn iana_zone(zone: &str) -> Option<chrono_tz::Tz> {
+    let name = zone.trim();
+    if !name.contains('/') {
+        return None;
+    }
+    name.parse::<chrono_tz::Tz>().ok()
+}
+
+// The zone named by the TZ environment variable: an IANA name, optionally
+// after a colon, or a path into the zoneinfo database. An empty TZ is UTC.
+pub fn tz_variable_zone(value: &str) -> Option<chrono_tz::Tz> {
+    let name = value.strip_prefix(':').unwrap_or(value);
+    if name.is_empty() {
+        return Some(chrono_tz::UTC);
+    }
+    let name = name.rsplit_once("zoneinfo/").map_or(name, |(_, x)| x);
+    name.parse::<chrono_tz::Tz>().ok()
+}
@@ -4,37 +4,242 @@ extern crate lazy_static;
 pub mod date_parser;
 
 use thiserror::Error;
+use serde::Serialize;
+use std::ops::{Range, RangeInclusive};
 use std::result;
 
 pub use date_parser::time::parse;
 pub use date_parser::time::parse_with_custom_time_and_year;
+pub use date_parser::time::parse_with_year_pivot;
+pub use date_parser::time::parse_with_options;
+pub use date_parser::parse_range;
+pub use date_parser::parse_range_with_options;
+pub use date_parser::ParseOptions;
+pub use date_parser::YearPivot;
+pub use date_parser::DateOrder;
+pub use date_parser::time::parse_in_zone;
+pub use date_parser::time::parse_in_zone_with_dst_policy;
+pub use date_parser::time::DstPolicy;
+pub use date_parser::time::WeekdayPolicy;
+pub use date_parser::time::strptime;
+pub use date_parser::time::strptime_with_custom_time_and_year;
+pub use date_parser::strftime;
+pub use date_parser::to_jisx0301;
+pub use date_parser::to_jisx0301_time;
+pub use date_parser::time::iso8601;
+pub use date_parser::time::rfc3339;
+pub use date_parser::time::xmlschema;
+pub use date_parser::time::rfc2822;
+pub use date_parser::time::httpdate;
+pub use date_parser::time::jisx0301;
+pub use date_parser::time::rfc9557;
+pub use date_parser::iso8601_interval;
+pub use date_parser::iso8601_interval_with_options;
+pub use date_parser::iso8601_repeating_interval;
+pub use date_parser::iso8601_repeating_interval_with_options;
+pub use date_parser::Interval;
+pub use date_parser::Intervals;
+pub use date_parser::RepeatingInterval;
 
 /// Convenience type alias for parse errors
 pub type Result<T, E = ParseError> = result::Result<T, E>;
 
-#[derive(Error, Debug)]
-pub enum ParseError {
-    #[error("no time information in {0}")]
-    MissingTimeInformationError(String),
-    #[error("{0} value {1} out of range")]
-    OutOfRangeError(String, String),
-    #[error("Error creating date")]
-    DateError(),
-    #[error("internal error: {0}")]
-    InternalError(String),
-    #[error("offset out of bounds")]
+/// What went wrong, for callers to tell the failures apart without going
+/// through the messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// There is no date or time in the input at all.
+    NoDateFound,
+    /// The input doesn't follow the format, either a strptime format or
+    /// one of the strict formats such as iso8601.
+    InvalidFormat,
+    /// A field is out of its range, like the 13th month or the 61st minute.
+    FieldOutOfRange,
+    /// The day is past the end of its month, like February 30th.
+    InvalidDayOfMonth,
+    /// The day of the year is past the end of the year.
+    InvalidYday,
+    /// The zone is neither an offset nor a zone name that is known.
+    UnknownZone,
+    /// The offset is a day or more away from UTC.
     OffsetOutOfBounds,
+    /// The year is beyond what a date can hold.
+    UnrepresentableYear,
+    /// The local time doesn't exist in the zone, being in a DST gap.
+    SkippedLocalTime,
+    /// The local time happens twice in the zone, being in a DST overlap.
+    AmbiguousLocalTime,
+    /// The day of the week isn't the one the date falls on, like Saturday
+    /// 1 June 2000, a Thursday.
+    WeekdayMismatch,
+    /// The input isn't a duration in any of the forms `parse_duration`
+    /// reads.
+    InvalidDuration,
+}
+
+/// The error of the functions returning times. Beyond the kind, it carries
+/// what is known of the failure: the input and the byte range of the
+/// offending part of it, the field (named as in `DateTime`, e.g. `mday`),
+/// its value and the values it may take.
+///
+/// ```
+/// let err = rb_date_parser::parse("2023-02-30 10:00").unwrap_err();
+/// assert_eq!(err.kind(), rb_date_parser::ErrorKind::InvalidDayOfMonth);
+/// assert_eq!(err.field(), Some("mday"));
+/// assert_eq!(err.allowed(), Some(1..=28));
+/// assert_eq!(err.span(), Some(0..10));
+/// assert_eq!(err.to_string(), "day value 30 out of range 1..=28 at 0..10 in `2023-02-30 10:00'");
+/// ```
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{}", self.message())]
+pub struct ParseError(Box<ErrorDetails>);
+
+#[derive(Debug, Clone, PartialEq)]
+struct ErrorDetails {
+    kind: ErrorKind,
+    input: Option<String>,
+    span: Option<Range<usize>>,
+    field: Option<&'static str>,
+    value: Option<String>,
+    allowed: Option<RangeInclusive<i64>>,
+    detail: Option<String>,
+}
+
+impl ParseError {
+    pub fn kind(&self) -> ErrorKind {
+        self.0.kind
+    }
+
+    pub fn input(&self) -> Option<&str> {
+        self.0.input.as_deref()
+    }
+
+    /// The byte range in the input of the text the field was read from.
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.0.span.clone()
+    }
+
+    pub fn field(&self) -> Option<&'static str> {
+        self.0.field
+    }
+
+    pub fn value(&self) -> Option<&str> {
+        self.0.value.as_deref()
+    }
+
+    pub fn allowed(&self) -> Option<RangeInclusive<i64>> {
+        self.0.allowed.clone()
+    }
+
+    /// The format for InvalidFormat, the zone for UnknownZone and the local
+    /// time errors, and the date for WeekdayMismatch.
+    pub fn detail(&self) -> Option<&str> {
+        self.0.detail.as_deref()
+    }
+
+    pub(crate) fn new(kind: ErrorKind) -> ParseError {
+        ParseError(Box::new(ErrorDetails { kind, input: None, span: None, field: None, value: None, allowed: None, detail: None }))
+    }
+
+    pub(crate) fn out_of_range(kind: ErrorKind, field: &'static str, value: impl ToString, allowed: Option<RangeInclusive<i64>>) -> ParseError {
+        let mut err = ParseError::new(kind);
+        err.0.field = Some(field);
+        err.0.value = Some(value.to_string());
+        err.0.allowed = allowed;
+        err
+    }
+
+    pub(crate) fn invalid_format(input: &str, format: &str) -> ParseError {
+        ParseError::new(ErrorKind::InvalidFormat).with_input(input).with_detail(format)
+    }
+
+    pub(crate) fn with_value(mut self, value: impl ToString) -> ParseError {
+        self.0.value = Some(value.to_string());
+        self
+    }
+
+    pub(crate) fn with_detail(mut self, detail: impl ToString) -> ParseError {
+        self.0.detail = Some(detail.to_string());
+        self
+    }
+
+    // Errors are raised where the fields are checked, which doesn't know
+    // the input; the callers that do fill it in on the way out.
+    pub(crate) fn with_input(mut self, input: &str) -> ParseError {
+        self.0.input.get_or_insert_with(|| input.to_owned());
+        self
+    }
+
+    pub(crate) fn with_span(mut self, span: Option<Range<usize>>) -> ParseError {
+        self.0.span = self.0.span.take().or(span);
+        self
+    }
+
+    fn message(&self) -> String {
+        let details = &self.0;
+        let field = match details.field.unwrap_or_default() {
+            "mon" => "month",
+            "mday" => "day",
+            "yday" => "day of the year",
+            "min" => "minute",
+            "sec" => "second",
+            "cweek" => "week",
+            "cwday" | "wday" => "day of the week",
+            "wnum0" | "wnum1" => "week number",
+            field => field,
+        };
+        let value = details.value.as_deref().unwrap_or_default();
+        let detail = details.detail.as_deref().unwrap_or_default();
+        let mut message = match details.kind {
+            ErrorKind::NoDateFound => "no time information".to_owned(),
+            ErrorKind::InvalidFormat => format!("invalid date or format - `{}' `{}'", details.input.as_deref().unwrap_or_default(), detail),
+            ErrorKind::FieldOutOfRange | ErrorKind::InvalidDayOfMonth | ErrorKind::InvalidYday | ErrorKind::OffsetOutOfBounds => format!("{} value {} out of range", field, value),
+            ErrorKind::UnknownZone => format!("unknown zone {}", detail),
+            ErrorKind::UnrepresentableYear => format!("year {} can't be represented", value),
+            ErrorKind::SkippedLocalTime => format!("local time {} does not exist in {}", value, detail),
+            ErrorKind::AmbiguousLocalTime => format!("local time {} is ambiguous in {}", value, detail),
+            ErrorKind::WeekdayMismatch => format!("{} {} doesn't match {}", field, value, detail),
+            ErrorKind::InvalidDuration => "invalid duration".to_owned(),
+        };
+        if details.kind == ErrorKind::InvalidFormat {
+            return message;
+        }
+        if let Some(allowed) = &details.allowed {
+            message += &format!(" {:?}", allowed);
+        }
+        if let Some(span) = &details.span {
+            message += &format!(" at {:?}", span);
+        }
+        if let Some(input) = &details.input {
+            message += &format!(" in `{}'", input);
+        }
+        message
+    }
 }
 
 pub use date_parser::DateTime;
+pub use date_parser::parse_candidates;
+pub use date_parser::parse_duration;
+pub use date_parser::CalendarDuration;
+pub use date_parser::parse_date;
+pub use date_parser::parse_date_with_today;
+pub use date_parser::Date;
+pub use date_parser::Candidate;
+pub use date_parser::date_parse_with_spans;
+pub use date_parser::FieldSpan;
+pub use date_parser::find_dates;
+pub use date_parser::DateMatch;
 
+/// The time zone times without a zone are read in: the one named by the
+/// TZ environment variable, like `chrono::Local` does, else the system
+/// time zone, else UTC. It is looked up once.
 pub fn get_current_timezone() -> chrono_tz::Tz {
     lazy_static! {
-        static ref LOCAL_TZ: chrono_tz::Tz = {
-            let tz_str = iana_time_zone::get_timezone().expect("Error getting local timezone");
-            let tz: chrono_tz::Tz = tz_str.parse().expect("Error parsing local timezone");
-            tz
-        };
+        static ref LOCAL_TZ: chrono_tz::Tz = std::env::var("TZ").ok()
+            .and_then(|tz_str| date_parser::tz_variable_zone(&tz_str))
+            .or_else(|| iana_time_zone::get_timezone().ok().and_then(|tz_str| tz_str.parse().ok()))
+            .unwrap_or(chrono_tz::UTC);
     }
     *LOCAL_TZ
 }